@@ -0,0 +1,78 @@
+#[derive(Debug, Clone)]
+pub enum AnimationTrigger {
+    // advance to the next frame every time the duration elapses
+    Time(std::time::Duration),
+    // advance only when the owner calls `next_frame`, e.g. on every march step
+    Event,
+}
+
+#[derive(Debug, Clone)]
+pub struct Animation {
+    frames: Vec<String>,
+    current: usize,
+    trigger: AnimationTrigger,
+    last_tick: std::time::Instant,
+}
+
+impl Animation {
+    pub fn new(frames: &[&str], trigger: AnimationTrigger) -> Self {
+        assert!(!frames.is_empty(), "animation needs at least one frame");
+
+        Self {
+            frames: frames.iter().map(|f| f.to_string()).collect(),
+            current: 0,
+            trigger,
+            last_tick: std::time::Instant::now(),
+        }
+    }
+
+    pub fn timed(frames: &[&str], frame_duration: std::time::Duration) -> Self {
+        Self::new(frames, AnimationTrigger::Time(frame_duration))
+    }
+
+    pub fn on_event(frames: &[&str]) -> Self {
+        Self::new(frames, AnimationTrigger::Event)
+    }
+
+    pub fn get_frame(&self) -> &str {
+        &self.frames[self.current]
+    }
+
+    pub fn get_frame_index(&self) -> usize {
+        self.current
+    }
+
+    pub fn get_frames(&self) -> &Vec<String> {
+        &self.frames
+    }
+
+    pub fn get_trigger(&self) -> AnimationTrigger {
+        self.trigger.clone()
+    }
+
+    pub fn next_frame(&mut self) {
+        self.current = (self.current + 1) % self.frames.len();
+    }
+
+    pub fn reset(&mut self) {
+        self.current = 0;
+        self.last_tick = std::time::Instant::now();
+    }
+
+    // pushes the frame timer forward, e.g. by the time the game was paused
+    pub fn shift_timers(&mut self, by: std::time::Duration) {
+        self.last_tick += by;
+    }
+
+    // advances time based animations, event based ones are left untouched
+    pub fn tick(&mut self) {
+        if let AnimationTrigger::Time(frame_duration) = self.trigger {
+            let now = std::time::Instant::now();
+
+            if now - self.last_tick >= frame_duration {
+                self.next_frame();
+                self.last_tick = now;
+            }
+        }
+    }
+}
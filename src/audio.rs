@@ -1,67 +1,422 @@
-use rodio::{source::Source, Decoder, OutputStream, Sink};
+use rodio::buffer::SamplesBuffer;
+use rodio::source::{Buffered, Source};
+use rodio::Decoder;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::io::BufReader;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
 
 use anyhow::{anyhow as error, Result};
 
+use crate::audio_backend::{AudioBackend, NullBackend, RodioBackend};
+use crate::container::Container;
+use crate::log;
+use crate::sounds::{SoundEvent, SoundManifest};
+use crate::synth::{self, Preset};
+
+// decoded or synthesized samples, clones share them and play from the start
+pub type Sound = Buffered<SamplesBuffer<f32>>;
+
+// a music track, played on the music channel until it ends or is replaced
+pub type MusicSource = Box<dyn Source<Item = f32> + Send>;
+
+// every sound plays on one channel, its volume is the master volume times
+// the channel's
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Channel {
+    Sfx,
+    Music,
+}
+
+// f32 bits of the volumes, 1.0 by default
+static VOLUME: AtomicU32 = AtomicU32::new(0x3f80_0000);
+static SFX_VOLUME: AtomicU32 = AtomicU32::new(0x3f80_0000);
+static MUSIC_VOLUME: AtomicU32 = AtomicU32::new(0x3f80_0000);
+static MUTED: AtomicBool = AtomicBool::new(false);
+// off for mono setups, every sound then plays centered
+static STEREO: AtomicBool = AtomicBool::new(true);
+// off while game events shouldn't be heard, e.g. during the attract demo
+static EVENTS_ENABLED: AtomicBool = AtomicBool::new(true);
+// false once opening the sound device failed
+static AVAILABLE: AtomicBool = AtomicBool::new(true);
+
+impl Channel {
+    fn volume(&self) -> &'static AtomicU32 {
+        match self {
+            Channel::Sfx => &SFX_VOLUME,
+            Channel::Music => &MUSIC_VOLUME,
+        }
+    }
+
+    // what sounds on this channel are played at right now
+    pub fn get_gain(&self) -> f32 {
+        if is_muted() {
+            return 0.0;
+        }
+
+        get_volume() * get_channel_volume(*self)
+    }
+}
+
+// the master volume
+pub fn set_volume(volume: f32) {
+    VOLUME.store(volume.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+    refresh();
+}
+
+pub fn get_volume() -> f32 {
+    f32::from_bits(VOLUME.load(Ordering::Relaxed))
+}
+
+pub fn set_channel_volume(channel: Channel, volume: f32) {
+    let volume = volume.clamp(0.0, 1.0).to_bits();
+    channel.volume().store(volume, Ordering::Relaxed);
+    refresh();
+}
+
+pub fn get_channel_volume(channel: Channel) -> f32 {
+    f32::from_bits(channel.volume().load(Ordering::Relaxed))
+}
+
+// silences everything without touching the volumes
+pub fn set_muted(muted: bool) {
+    MUTED.store(muted, Ordering::Relaxed);
+    refresh();
+}
+
+pub fn is_muted() -> bool {
+    MUTED.load(Ordering::Relaxed)
+}
+
+// turns the sounds of `play_event` off and back on; while off, events
+// don't start their cooldowns either
+pub fn set_events_enabled(enabled: bool) {
+    EVENTS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_events_enabled() -> bool {
+    EVENTS_ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_stereo(stereo: bool) {
+    STEREO.store(stereo, Ordering::Relaxed);
+}
+
+pub fn is_stereo() -> bool {
+    STEREO.load(Ordering::Relaxed)
+}
+
+// where `x` lies in `area` as a stereo position, -1.0 is the left edge,
+// 0.0 the middle and 1.0 the right edge
+pub fn get_pan(x: usize, area: &Container) -> f32 {
+    let left = area.top.x as f32;
+    let width = area.bottom.x.saturating_sub(area.top.x) as f32;
+    if width <= 0.0 {
+        return 0.0;
+    }
+
+    ((x as f32 - left) / width * 2.0 - 1.0).clamp(-1.0, 1.0)
+}
+
+// sounds already playing pick up volume changes too
+fn refresh() {
+    if let Some(backend) = BACKEND.lock().unwrap().as_mut() {
+        let _ = backend.refresh();
+    }
+}
+
+// extra sounds for players who follow the game by ear
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cue {
+    // the ship ran into the side of the playfield
+    Edge,
+    WaveStart,
+}
+
+impl Cue {
+    pub fn get_event(&self) -> SoundEvent {
+        match self {
+            Cue::Edge => SoundEvent::Edge,
+            Cue::WaveStart => SoundEvent::WaveStart,
+        }
+    }
+}
+
+// renders every synth preset and decodes every sound in the manifest, so
+// playing one later is just a copy; returns one error per sound that could
+// not be loaded, those stay silent
+pub fn preload() -> Vec<anyhow::Error> {
+    let mut paths: Vec<String> = Preset::all().iter().map(|p| p.get_sound()).collect();
+    for entry in EventSounds::get()
+        .lock()
+        .unwrap()
+        .manifest
+        .get_entries()
+        .values()
+    {
+        if !paths.contains(&entry.sound) {
+            paths.push(entry.sound.clone());
+        }
+    }
+
+    paths
+        .iter()
+        .filter_map(|path| SoundCache::get().load(path).err())
+        .collect()
+}
+
+// replaces the sounds the game events play, e.g. after the config changed
+pub fn set_sound_manifest(manifest: SoundManifest) {
+    let mut events = EventSounds::get().lock().unwrap();
+
+    events.manifest = manifest;
+    events.last_played.clear();
+}
+
+// plays the manifest's sound for `event` in the middle
+pub fn play_event(event: SoundEvent) -> Result<()> {
+    play_event_at(event, 0.0)
+}
+
+// plays the manifest's sound for `event` at stereo position `pan`, see
+// `get_pan`, unless it is still cooling down
+pub fn play_event_at(event: SoundEvent, pan: f32) -> Result<()> {
+    if !is_events_enabled() {
+        return Ok(());
+    }
+
+    let entry = {
+        let mut events = EventSounds::get().lock().unwrap();
+        let entry = match events.manifest.get(event) {
+            Some(entry) => entry.clone(),
+            None => return Ok(()),
+        };
+
+        let now = std::time::Instant::now();
+        if let Some(last) = events.last_played.get(&event) {
+            if now - *last < entry.get_cooldown() {
+                return Ok(());
+            }
+        }
+        events.last_played.insert(event, now);
+
+        entry
+    };
+
+    play_sound(&entry.sound, Channel::Sfx, entry.volume, pan)
+}
+
+// config changes to the synth effects, sounds already rendered with the
+// old values are dropped from the cache
+pub fn set_synth_overrides(overrides: &BTreeMap<String, serde_json::Value>) {
+    let cache = SoundCache::get();
+
+    *cache.synth_overrides.lock().unwrap() = overrides.clone();
+    cache
+        .sounds
+        .lock()
+        .unwrap()
+        .retain(|path, _| !path.starts_with(synth::PREFIX));
+}
+
+// fades the current track out while `source` fades in
+pub fn play_music(source: MusicSource, fade: std::time::Duration) -> Result<()> {
+    with_backend(|backend| backend.play_music(source, fade))
+}
+
+pub fn stop_music(fade: std::time::Duration) -> Result<()> {
+    with_backend(|backend| backend.stop_music(fade))
+}
+
+pub fn pause_music(paused: bool) -> Result<()> {
+    with_backend(|backend| backend.pause_music(paused))
+}
+
+// playback rate of the current track, 2.0 plays it twice as fast (and higher)
+pub fn set_music_speed(speed: f32) -> Result<()> {
+    with_backend(|backend| backend.set_music_speed(speed))
+}
+
+// a sound file that loops forever, e.g. a user-supplied march
+pub fn load_music(path: &str) -> Result<MusicSource> {
+    let sound = SoundCache::decode(path)?;
+
+    Ok(Box::new(sound.repeat_infinite()))
+}
+
+pub fn play_cue(cue: Cue, pan: f32) -> Result<()> {
+    play_event_at(cue.get_event(), pan)
+}
+
+// the sound a game object makes for one of its events
 #[derive(Debug, Clone)]
 pub struct GameObjectSound {
-    path: String,
+    event: SoundEvent,
 }
 
 impl GameObjectSound {
-    pub fn new(path: &str) -> Self {
-        Self {
-            path: path.to_string(),
-        }
+    pub fn new(event: SoundEvent) -> Self {
+        Self { event }
+    }
+
+    pub fn get_event(&self) -> SoundEvent {
+        self.event
     }
+
     pub fn play(&self) -> Result<()> {
-        GameAudio::play_audio(&self.path)?;
-        Ok(())
+        play_event(self.event)
+    }
+
+    // heard from where `x` lies in `area`
+    pub fn play_at(&self, x: usize, area: &Container) -> Result<()> {
+        play_event_at(self.event, get_pan(x, area))
     }
 }
 
-pub struct GameAudio {
-    hit_path: String,
-    on_hit_path: String,
+// the manifest and when each event last played, for the cooldowns
+struct EventSounds {
+    manifest: SoundManifest,
+    last_played: HashMap<SoundEvent, std::time::Instant>,
 }
 
-impl GameAudio {
-    pub fn new() -> Self {
-        Self {
-            hit_path: "assets/sounds/hit.mp3".to_string(),
-            on_hit_path: "assets/sounds/on_hit.mp3".to_string(),
-        }
+static EVENT_SOUNDS: OnceLock<Mutex<EventSounds>> = OnceLock::new();
+
+impl EventSounds {
+    fn get() -> &'static Mutex<Self> {
+        EVENT_SOUNDS.get_or_init(|| {
+            Mutex::new(Self {
+                manifest: SoundManifest::default(),
+                last_played: HashMap::new(),
+            })
+        })
     }
+}
 
-    fn play(path: &str) -> Result<()> {
-        let (_stream, stream_handle) = OutputStream::try_default()?;
-        let file = BufReader::new(File::open(path)?);
-        let source = Decoder::new(file)?;
-        let sink = Sink::try_new(&stream_handle)?;
-        sink.append(source);
-        sink.sleep_until_end();
-        Ok(())
-    }
-    pub fn play_audio(path: &str) -> Result<()> {
-        let path_t = path.to_string();
-        std::thread::spawn(move || {
-            let result = Self::play(&path_t);
-            if let Err(e) = result {
-                println!("Failed to playu audio: {:?}", e);
-            }
-        });
+// decoded and synthesized sounds, shared by every backend
+struct SoundCache {
+    // None for sounds that failed to load, they are reported only once
+    sounds: Mutex<HashMap<String, Option<Sound>>>,
+    synth_overrides: Mutex<BTreeMap<String, serde_json::Value>>,
+}
+
+static CACHE: OnceLock<SoundCache> = OnceLock::new();
+
+// None until the first sound, which opens the sound device unless
+// `set_backend` picked something else before
+static BACKEND: Mutex<Option<Box<dyn AudioBackend>>> = Mutex::new(None);
+
+// the game tick sounds are logged with, see `RecordingBackend`
+static TICK: AtomicU64 = AtomicU64::new(0);
 
-        Ok(())
+impl SoundCache {
+    fn get() -> &'static Self {
+        CACHE.get_or_init(|| Self {
+            sounds: Mutex::new(HashMap::new()),
+            synth_overrides: Mutex::new(BTreeMap::new()),
+        })
     }
 
-    pub fn play_fire(&self) -> Result<()> {
-        Self::play_audio(&self.hit_path)?;
-        Ok(())
+    fn decode(path: &str) -> Result<Sound> {
+        let file = File::open(path).map_err(|e| error!("{}: {}", path, e))?;
+        let decoder = Decoder::new(BufReader::new(file)).map_err(|e| error!("{}: {}", path, e))?;
+
+        let channels = decoder.channels();
+        let sample_rate = decoder.sample_rate();
+        let samples: Vec<f32> = decoder.convert_samples().collect();
+
+        Ok(SamplesBuffer::new(channels, sample_rate, samples).buffered())
+    }
+
+    // "synth:<name>" is rendered by the synthesizer, anything else is a file
+    fn render(&self, path: &str) -> Result<Sound> {
+        let name = match path.strip_prefix(synth::PREFIX) {
+            Some(name) => name,
+            None => return Self::decode(path),
+        };
+
+        let params = synth::get_params(name, &self.synth_overrides.lock().unwrap())?;
+        let samples = synth::render(&params)?;
+
+        Ok(SamplesBuffer::new(1, synth::SAMPLE_RATE, samples).buffered())
+    }
+
+    // decodes `path` into the cache unless it was tried before
+    fn load(&self, path: &str) -> Result<Option<Sound>> {
+        let mut sounds = self.sounds.lock().unwrap();
+        if let Some(sound) = sounds.get(path) {
+            return Ok(sound.clone());
+        }
+
+        match self.render(path) {
+            Ok(sound) => {
+                sounds.insert(path.to_string(), Some(sound.clone()));
+                Ok(Some(sound))
+            }
+            Err(e) => {
+                sounds.insert(path.to_string(), None);
+                Err(e)
+            }
+        }
+    }
+}
+
+// replaces the backend every sound and track goes to from now on, e.g. a
+// `NullBackend` for headless runs or a `RecordingBackend` in tests; it is
+// one per process, so tests that set it have to take turns
+pub fn set_backend(backend: Box<dyn AudioBackend>) {
+    *BACKEND.lock().unwrap() = Some(backend);
+}
+
+// opens the sound device unless a backend was set already; without one the
+// game carries on silently, see `is_available`
+pub fn start() {
+    BACKEND.lock().unwrap().get_or_insert_with(open_device);
+}
+
+// false when there is no sound device, the reason is in the log file
+pub fn is_available() -> bool {
+    AVAILABLE.load(Ordering::Relaxed)
+}
+
+fn open_device() -> Box<dyn AudioBackend> {
+    match RodioBackend::new() {
+        Ok(backend) => Box::new(backend),
+        Err(e) => {
+            AVAILABLE.store(false, Ordering::Relaxed);
+            let _ = log::write(&format!("audio: {}, playing without sound", e));
+            Box::new(NullBackend)
+        }
     }
+}
+
+fn with_backend<T>(f: impl FnOnce(&mut dyn AudioBackend) -> T) -> T {
+    let mut backend = BACKEND.lock().unwrap();
+    let backend = backend.get_or_insert_with(open_device);
+
+    f(backend.as_mut())
+}
+
+pub fn set_tick(tick: u64) {
+    TICK.store(tick, Ordering::Relaxed);
+}
+
+pub fn get_tick() -> u64 {
+    TICK.load(Ordering::Relaxed)
+}
+
+// hands the sound to the backend, returns right away; sounds missing from
+// `preload` are decoded on their first play. `volume` scales the channel's
+// and `pan` is ignored while stereo is off
+pub fn play_sound(path: &str, channel: Channel, volume: f32, pan: f32) -> Result<()> {
+    let pan = if is_stereo() {
+        pan.clamp(-1.0, 1.0)
+    } else {
+        0.0
+    };
 
-    pub fn play_on_hit(&self) -> Result<()> {
-        Self::play_audio(&self.on_hit_path)?;
-        Ok(())
+    match SoundCache::get().load(path)? {
+        Some(sound) => with_backend(|backend| backend.play(path, sound, channel, volume, pan)),
+        None => Ok(()),
     }
 }
@@ -0,0 +1,517 @@
+use rodio::{OutputStream, OutputStreamHandle, Sink, Source};
+use std::collections::{HashMap, VecDeque};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow as error, Result};
+
+use crate::audio::{self, Channel, MusicSource, Sound};
+use crate::log;
+
+// the engine thread has nobody to return errors to and the terminal is in
+// raw mode, so they go to the log file
+fn log_error(message: &str) {
+    let _ = log::write(&format!("audio: {}", message));
+}
+
+// copies of one sound that may play at the same time, rapid fire cuts off
+// the oldest instead of piling up
+const MAX_VOICES_PER_SOUND: usize = 4;
+
+// how often the engine steps crossfades
+const FADE_STEP: std::time::Duration = std::time::Duration::from_millis(20);
+
+// where sounds end up; the game only ever talks to one of these through
+// the functions in `audio`
+pub trait AudioBackend: Send {
+    // `path` names the sound, `sound` holds its samples, `volume` scales
+    // the channel's and `pan` runs from -1.0 (left) to 1.0 (right)
+    fn play(
+        &mut self,
+        path: &str,
+        sound: Sound,
+        channel: Channel,
+        volume: f32,
+        pan: f32,
+    ) -> Result<()>;
+
+    // volumes or mute changed
+    fn refresh(&mut self) -> Result<()>;
+
+    // fades the current track out while `source` fades in
+    fn play_music(&mut self, source: MusicSource, fade: std::time::Duration) -> Result<()>;
+
+    fn stop_music(&mut self, fade: std::time::Duration) -> Result<()>;
+
+    fn pause_music(&mut self, paused: bool) -> Result<()>;
+
+    fn set_music_speed(&mut self, speed: f32) -> Result<()>;
+}
+
+enum Request {
+    Play {
+        path: String,
+        sound: Sound,
+        channel: Channel,
+        volume: f32,
+        pan: f32,
+    },
+    Refresh,
+    PlayMusic {
+        source: MusicSource,
+        fade: std::time::Duration,
+    },
+    StopMusic {
+        fade: std::time::Duration,
+    },
+    PauseMusic(bool),
+    MusicSpeed(f32),
+}
+
+// moves a sound towards one speaker by turning the other one down, a mono
+// sound becomes stereo; sounds with more channels are left alone
+struct Panned<S> {
+    input: S,
+    input_channels: u16,
+    gains: [f32; 2],
+    // the right half of the current frame
+    right: Option<f32>,
+    // next channel of a stereo input
+    channel: usize,
+}
+
+impl<S: Source<Item = f32>> Panned<S> {
+    fn new(input: S, pan: f32) -> Self {
+        let input_channels = input.channels();
+
+        Self {
+            input,
+            input_channels,
+            gains: [(1.0 - pan).min(1.0), (1.0 + pan).min(1.0)],
+            right: None,
+            channel: 0,
+        }
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for Panned<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        match self.input_channels {
+            1 => {
+                if let Some(right) = self.right.take() {
+                    return Some(right);
+                }
+
+                let sample = self.input.next()?;
+                self.right = Some(sample * self.gains[1]);
+                Some(sample * self.gains[0])
+            }
+            2 => {
+                let sample = self.input.next()?;
+                let gain = self.gains[self.channel];
+                self.channel = 1 - self.channel;
+                Some(sample * gain)
+            }
+            _ => self.input.next(),
+        }
+    }
+}
+
+impl<S: Source<Item = f32>> Source for Panned<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        match self.input_channels {
+            1 => self.input.current_frame_len().map(|len| len * 2),
+            _ => self.input.current_frame_len(),
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        self.input_channels.max(2)
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<std::time::Duration> {
+        self.input.total_duration()
+    }
+}
+
+struct Voice {
+    sink: Sink,
+    channel: Channel,
+    volume: f32,
+}
+
+// a music sink fading in, or out once another track took over
+struct MusicVoice {
+    sink: Sink,
+    fade_started: std::time::Instant,
+    fade: std::time::Duration,
+    fading_out: bool,
+}
+
+impl MusicVoice {
+    // 0.0 to 1.0 over the fade, the other way round when fading out
+    fn get_ramp(&self) -> f32 {
+        let fade = self.fade.as_secs_f32();
+        let progress = if fade > 0.0 {
+            (self.fade_started.elapsed().as_secs_f32() / fade).min(1.0)
+        } else {
+            1.0
+        };
+
+        if self.fading_out {
+            1.0 - progress
+        } else {
+            progress
+        }
+    }
+
+    fn fade_out(&mut self, fade: std::time::Duration) {
+        // a track that is still fading in starts fading out from where it is
+        let ramp = self.get_ramp();
+        self.fade_started = std::time::Instant::now() - fade.mul_f32(1.0 - ramp);
+        self.fade = fade;
+        self.fading_out = true;
+    }
+}
+
+// lives on the engine thread next to the output stream
+struct Mixer {
+    stream_handle: OutputStreamHandle,
+    voices: HashMap<String, VecDeque<Voice>>,
+    music: Option<MusicVoice>,
+    fading_music: Vec<MusicVoice>,
+}
+
+impl Mixer {
+    fn new(stream_handle: OutputStreamHandle) -> Self {
+        Self {
+            stream_handle,
+            voices: HashMap::new(),
+            music: None,
+            fading_music: vec![],
+        }
+    }
+
+    fn handle(&mut self, request: Request) {
+        match request {
+            Request::Play {
+                path,
+                sound,
+                channel,
+                volume,
+                pan,
+            } => {
+                let playing = self.voices.entry(path.clone()).or_default();
+                playing.retain(|voice| !voice.sink.empty());
+
+                if playing.len() >= MAX_VOICES_PER_SOUND {
+                    if let Some(oldest) = playing.pop_front() {
+                        oldest.sink.stop();
+                    }
+                }
+
+                match Sink::try_new(&self.stream_handle) {
+                    Ok(sink) => {
+                        sink.set_volume(channel.get_gain() * volume);
+                        if pan == 0.0 {
+                            sink.append(sound);
+                        } else {
+                            sink.append(Panned::new(sound, pan));
+                        }
+                        playing.push_back(Voice {
+                            sink,
+                            channel,
+                            volume,
+                        });
+                    }
+                    Err(e) => log_error(&format!("Failed to play {}: {}", path, e)),
+                }
+            }
+            Request::Refresh => {
+                for voice in self.voices.values().flatten() {
+                    voice
+                        .sink
+                        .set_volume(voice.channel.get_gain() * voice.volume);
+                }
+            }
+            Request::PlayMusic { source, fade } => {
+                self.stop_music(fade);
+
+                match Sink::try_new(&self.stream_handle) {
+                    Ok(sink) => {
+                        sink.set_volume(0.0);
+                        sink.append(source);
+                        self.music = Some(MusicVoice {
+                            sink,
+                            fade_started: std::time::Instant::now(),
+                            fade,
+                            fading_out: false,
+                        });
+                    }
+                    Err(e) => log_error(&format!("Failed to play music: {}", e)),
+                }
+            }
+            Request::StopMusic { fade } => self.stop_music(fade),
+            Request::PauseMusic(paused) => {
+                for music in self.music.iter().chain(&self.fading_music) {
+                    if paused {
+                        music.sink.pause();
+                    } else {
+                        music.sink.play();
+                    }
+                }
+            }
+            Request::MusicSpeed(speed) => {
+                if let Some(music) = &self.music {
+                    music.sink.set_speed(speed);
+                }
+            }
+        }
+    }
+
+    fn stop_music(&mut self, fade: std::time::Duration) {
+        if let Some(mut music) = self.music.take() {
+            music.fade_out(fade);
+            self.fading_music.push(music);
+        }
+    }
+
+    // steps the crossfades, also picks up music volume changes
+    fn update_music(&mut self) {
+        let gain = Channel::Music.get_gain();
+
+        for music in self.music.iter().chain(&self.fading_music) {
+            music.sink.set_volume(gain * music.get_ramp());
+        }
+
+        self.fading_music.retain(|music| music.get_ramp() > 0.0);
+    }
+}
+
+// the sound device through rodio; one thread owns the output stream for the
+// whole game and sounds are sent to it and mixed there, instead of every
+// sound opening its own device
+pub struct RodioBackend {
+    sender: Sender<Request>,
+}
+
+impl RodioBackend {
+    // waits until the engine thread opened the sound device, fails when
+    // there is none
+    pub fn new() -> Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let (opened_sender, opened) = mpsc::channel();
+
+        std::thread::spawn(move || Self::run(receiver, opened_sender));
+
+        opened
+            .recv()
+            .map_err(|_| error!("Audio engine has stopped"))??;
+
+        Ok(Self { sender })
+    }
+
+    fn run(receiver: Receiver<Request>, opened: Sender<Result<()>>) {
+        // the stream is not Send, it has to be opened on this thread
+        let (_stream, stream_handle) = match OutputStream::try_default() {
+            Ok(stream) => stream,
+            Err(e) => {
+                let _ = opened.send(Err(error!("Failed to open audio output: {}", e)));
+                return;
+            }
+        };
+        let _ = opened.send(Ok(()));
+
+        let mut mixer = Mixer::new(stream_handle);
+
+        loop {
+            // wakes up between requests to keep crossfades moving
+            match receiver.recv_timeout(FADE_STEP) {
+                Ok(request) => mixer.handle(request),
+                Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            mixer.update_music();
+        }
+    }
+
+    fn send(&self, request: Request) -> Result<()> {
+        self.sender
+            .send(request)
+            .map_err(|_| error!("Audio engine has stopped"))
+    }
+}
+
+impl AudioBackend for RodioBackend {
+    fn play(
+        &mut self,
+        path: &str,
+        sound: Sound,
+        channel: Channel,
+        volume: f32,
+        pan: f32,
+    ) -> Result<()> {
+        self.send(Request::Play {
+            path: path.to_string(),
+            sound,
+            channel,
+            volume,
+            pan,
+        })
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        self.send(Request::Refresh)
+    }
+
+    fn play_music(&mut self, source: MusicSource, fade: std::time::Duration) -> Result<()> {
+        self.send(Request::PlayMusic { source, fade })
+    }
+
+    fn stop_music(&mut self, fade: std::time::Duration) -> Result<()> {
+        self.send(Request::StopMusic { fade })
+    }
+
+    fn pause_music(&mut self, paused: bool) -> Result<()> {
+        self.send(Request::PauseMusic(paused))
+    }
+
+    fn set_music_speed(&mut self, speed: f32) -> Result<()> {
+        self.send(Request::MusicSpeed(speed))
+    }
+}
+
+// plays nothing, for headless runs and machines without a sound device
+#[derive(Debug, Clone, Default)]
+pub struct NullBackend;
+
+impl AudioBackend for NullBackend {
+    fn play(
+        &mut self,
+        _path: &str,
+        _sound: Sound,
+        _channel: Channel,
+        _volume: f32,
+        _pan: f32,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn play_music(&mut self, _source: MusicSource, _fade: std::time::Duration) -> Result<()> {
+        Ok(())
+    }
+
+    fn stop_music(&mut self, _fade: std::time::Duration) -> Result<()> {
+        Ok(())
+    }
+
+    fn pause_music(&mut self, _paused: bool) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_music_speed(&mut self, _speed: f32) -> Result<()> {
+        Ok(())
+    }
+}
+
+// what a `RecordingBackend` was asked to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum AudioEvent {
+    Sound {
+        path: String,
+        channel: Channel,
+        volume: f32,
+        pan: f32,
+    },
+    MusicStarted,
+    MusicStopped,
+    MusicPaused(bool),
+    MusicSpeed(f32),
+}
+
+// plays nothing but logs every request with the game tick it came in on;
+// clones share the log, so keep one to look at after handing the other
+// to `audio::set_backend`
+#[derive(Debug, Clone, Default)]
+pub struct RecordingBackend {
+    log: Arc<Mutex<Vec<(u64, AudioEvent)>>>,
+}
+
+impl RecordingBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_log(&self) -> Vec<(u64, AudioEvent)> {
+        self.log.lock().unwrap().clone()
+    }
+
+    // the paths of the sounds played so far, oldest first
+    pub fn get_sounds(&self) -> Vec<String> {
+        self.get_log()
+            .into_iter()
+            .filter_map(|(_, event)| match event {
+                AudioEvent::Sound { path, .. } => Some(path),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.log.lock().unwrap().clear();
+    }
+
+    fn record(&self, event: AudioEvent) -> Result<()> {
+        self.log.lock().unwrap().push((audio::get_tick(), event));
+        Ok(())
+    }
+}
+
+impl AudioBackend for RecordingBackend {
+    fn play(
+        &mut self,
+        path: &str,
+        _sound: Sound,
+        channel: Channel,
+        volume: f32,
+        pan: f32,
+    ) -> Result<()> {
+        self.record(AudioEvent::Sound {
+            path: path.to_string(),
+            channel,
+            volume,
+            pan,
+        })
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn play_music(&mut self, _source: MusicSource, _fade: std::time::Duration) -> Result<()> {
+        self.record(AudioEvent::MusicStarted)
+    }
+
+    fn stop_music(&mut self, _fade: std::time::Duration) -> Result<()> {
+        self.record(AudioEvent::MusicStopped)
+    }
+
+    fn pause_music(&mut self, paused: bool) -> Result<()> {
+        self.record(AudioEvent::MusicPaused(paused))
+    }
+
+    fn set_music_speed(&mut self, speed: f32) -> Result<()> {
+        self.record(AudioEvent::MusicSpeed(speed))
+    }
+}
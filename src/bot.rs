@@ -0,0 +1,103 @@
+use crate::container::Direction;
+use crate::game::Game;
+use crate::keyboard::Command;
+use crate::weapon::BULLET_TICK_DURATION;
+
+// enemy bullets this many rows above the ship or closer are dodged
+const DANGER_ROWS: usize = 4;
+
+// plays the demo game by picking the same commands a player's keys map to
+pub struct Bot {
+    reaction_time: std::time::Duration,
+    last_decision: std::time::Instant,
+}
+
+impl Bot {
+    pub fn new(reaction_time: std::time::Duration) -> Self {
+        Self {
+            reaction_time,
+            last_decision: std::time::Instant::now(),
+        }
+    }
+
+    pub fn get_reaction_time(&self) -> std::time::Duration {
+        self.reaction_time
+    }
+
+    pub fn set_reaction_time(&mut self, reaction_time: std::time::Duration) {
+        self.reaction_time = reaction_time;
+    }
+
+    // dodge incoming bullets first, otherwise line up under the nearest
+    // alien and fire; at most one command per reaction time
+    pub fn decide(&mut self, game: &Game) -> Option<Command> {
+        let now = std::time::Instant::now();
+        if now - self.last_decision < self.reaction_time {
+            return None;
+        }
+        self.last_decision = now;
+
+        let ship = game.get_ship().get_container();
+        let gun_x = ship.top.x + game.get_ship().get_width() / 2;
+
+        for eb in game.get_enemy_bullets() {
+            if eb.is_destroyed() {
+                continue;
+            }
+
+            let pos = eb.get_pos();
+            let above = pos.y < ship.top.y && ship.top.y - pos.y <= DANGER_ROWS;
+            let in_line = pos.x + 1 >= ship.top.x && pos.x <= ship.bottom.x + 1;
+
+            if above && in_line {
+                // run to the side with more room
+                let playable_area = game.get_playablearea();
+                let room_left = pos.x.saturating_sub(playable_area.top.x);
+                let room_right = playable_area.bottom.x.saturating_sub(pos.x);
+
+                return if room_right >= room_left {
+                    Some(Command::MoveRight)
+                } else {
+                    Some(Command::MoveLeft)
+                };
+            }
+        }
+
+        // aim where the alien will be once a bullet climbs up to it,
+        // ignoring the turn at the edges
+        let bullet_tick = game
+            .get_bullet()
+            .as_ref()
+            .map_or(BULLET_TICK_DURATION, |b| b.get_tick_duration());
+        let move_duration = game.get_enemy_move_duration().max(bullet_tick);
+        let direction = game.get_enemy_direction();
+
+        let target_x = game
+            .get_enemies()
+            .iter()
+            .filter(|e| !e.is_destroyed())
+            .map(|e| {
+                let pos = e.get_pos();
+                let rows = ship.top.y.saturating_sub(pos.y) as u32;
+                let steps = (bullet_tick * rows).as_millis() / move_duration.as_millis();
+                let steps = steps as usize;
+
+                match direction {
+                    Direction::LEFT => pos.x.saturating_sub(steps),
+                    Direction::RIGHT => pos.x + steps,
+                    _ => pos.x,
+                }
+            })
+            .min_by_key(|x| x.abs_diff(gun_x))?;
+
+        if target_x < gun_x {
+            Some(Command::MoveLeft)
+        } else if target_x > gun_x {
+            Some(Command::MoveRight)
+        } else if game.get_bullet().is_none() {
+            Some(Command::Fire)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::game::Difficulty;
+use crate::palette::Palette;
+use crate::starfield::StarDensity;
+
+const CONFIG_FILE: &str = "config.json";
+
+// per-user directory for the config and other saved state:
+// $XDG_CONFIG_HOME/invader, ~/.config/invader or %APPDATA%\invader
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(dir).join("invader"));
+    }
+
+    if let Some(dir) = std::env::var_os("APPDATA") {
+        return Some(PathBuf::from(dir).join("invader"));
+    }
+
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config").join("invader"))
+}
+
+// key names as understood by `keyboard::parse_key`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub left: String,
+    pub right: String,
+    pub fire: String,
+    pub pause: String,
+    pub screenshot: String,
+    pub record: String,
+    pub mute: String,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            left: "Left".to_string(),
+            right: "Right".to_string(),
+            fire: "Space".to_string(),
+            pause: "p".to_string(),
+            screenshot: "s".to_string(),
+            record: "r".to_string(),
+            mute: "m".to_string(),
+        }
+    }
+}
+
+// missing fields fall back to their defaults, so older config files keep loading
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    // master volume, the channel volumes scale it
+    pub volume: f32,
+    pub sfx_volume: f32,
+    pub music_volume: f32,
+    pub muted: bool,
+    // sounds come from where they happen on screen, off for mono setups
+    pub stereo: bool,
+    // a sound file to loop instead of the built-in march, empty for none
+    pub music_file: String,
+    // changes to the synth effects by name, e.g. {"laser": {"frequency": 900.0}}
+    pub synth: BTreeMap<String, serde_json::Value>,
+    // changes to the sound manifest by event, e.g. {"enemy_fire": {"volume": 0.3}}
+    pub sounds: BTreeMap<String, serde_json::Value>,
+    pub key_bindings: KeyBindings,
+    pub theme: String,
+    pub language: String,
+    pub difficulty: Difficulty,
+    pub starting_lives: usize,
+    pub frame_rate: u32,
+    pub starfield: bool,
+    pub star_density: StarDensity,
+
+    // accessibility
+    pub palette: Palette,
+    pub reduced_motion: bool,
+    pub audio_cues: bool,
+
+    pub formation_rows: usize,
+    pub formation_cols: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            sfx_volume: 1.0,
+            music_volume: 1.0,
+            muted: false,
+            stereo: true,
+            music_file: String::new(),
+            synth: BTreeMap::new(),
+            sounds: BTreeMap::new(),
+            key_bindings: KeyBindings::default(),
+            theme: "auto".to_string(),
+            language: "auto".to_string(),
+            difficulty: Difficulty::Normal,
+            starting_lives: 3,
+            frame_rate: 100,
+            starfield: true,
+            star_density: StarDensity::Medium,
+            palette: Palette::Standard,
+            reduced_motion: false,
+            audio_cues: false,
+            formation_rows: 5,
+            formation_cols: 2,
+        }
+    }
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(CONFIG_FILE))
+    }
+
+    // a missing file is not an error, it just means nothing was saved yet
+    pub fn load() -> Result<Self> {
+        let path = match Self::path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Self::default()),
+        };
+
+        let data = std::fs::read_to_string(path)?;
+        let config = serde_json::from_str(&data)?;
+
+        Ok(config)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = match Self::path() {
+            Some(path) => path,
+            None => return Err(anyhow::anyhow!("No config directory found")),
+        };
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    // milliseconds between two frames of the main loop
+    pub fn get_frame_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(1000 / self.frame_rate.max(1) as u64)
+    }
+}
@@ -23,6 +23,28 @@ impl Container {
     pub fn get_height(&self) -> usize {
         self.bottom.y - self.top.y
     }
+
+    // the area left inside the container once its padding is applied
+    pub fn inner(&self) -> Container {
+        let top = Point {
+            x: (self.top.x + self.padding_horizontal).min(self.bottom.x),
+            y: (self.top.y + self.padding_vertical).min(self.bottom.y),
+        };
+        let bottom = Point {
+            x: self
+                .bottom
+                .x
+                .saturating_sub(self.padding_horizontal)
+                .max(top.x),
+            y: self
+                .bottom
+                .y
+                .saturating_sub(self.padding_vertical)
+                .max(top.y),
+        };
+
+        Container::new(top, bottom)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -4,13 +4,15 @@ use crate::container::{Container, Point};
 
 use anyhow::Result;
 
+use crate::animation::Animation;
 use crate::audio::GameObjectSound;
 use crate::gobj::GameObject;
+use crate::sounds::SoundEvent;
 
 pub struct SmallAlien {
     pub gobj: GameObject,
     points: usize,
-    hit_sound: GameObjectSound,
+    fire_sound: GameObjectSound,
     on_hit_sound: GameObjectSound,
 }
 
@@ -29,7 +31,8 @@ impl DerefMut for SmallAlien {
 }
 
 impl SmallAlien {
-    pub fn new(x: usize, y: usize) -> Self {
+    // `frames` are the theme's alien glyphs
+    pub fn new(x: usize, y: usize, frames: &[String]) -> Self {
         let container = Container {
             top: Point { x, y },
             bottom: Point { x, y },
@@ -37,17 +40,19 @@ impl SmallAlien {
             padding_horizontal: 0,
         };
 
-        let symbol = "⍾";
+        let frames: Vec<&str> = frames.iter().map(|f| f.as_str()).collect();
         let points = 10;
 
-        let gobj = GameObject::new(container, symbol);
-        let hit_sound = GameObjectSound::new("assets/sounds/hit.mp3");
-        let on_hit_sound = GameObjectSound::new("assets/sounds/on_hit.mp3");
+        let mut gobj = GameObject::new(container, frames[0]);
+        gobj.set_animation(Animation::on_event(&frames));
+
+        let fire_sound = GameObjectSound::new(SoundEvent::EnemyFire);
+        let on_hit_sound = GameObjectSound::new(SoundEvent::AlienDestroyed);
 
         Self {
             gobj,
             points,
-            hit_sound,
+            fire_sound,
             on_hit_sound,
         }
     }
@@ -56,12 +61,13 @@ impl SmallAlien {
         self.points
     }
 
-    pub fn fire(&self) -> Result<()> {
-        self.hit_sound.play()
+    // the sounds are heard from where the alien is in `area`
+    pub fn fire(&self, area: &Container) -> Result<()> {
+        self.fire_sound.play_at(self.get_pos().x, area)
     }
 
-    pub fn destroy(&mut self) -> Result<()> {
+    pub fn destroy(&mut self, area: &Container) -> Result<()> {
         self.gobj.destroy();
-        self.on_hit_sound.play()
+        self.on_hit_sound.play_at(self.get_pos().x, area)
     }
 }
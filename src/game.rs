@@ -1,10 +1,23 @@
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
+use crate::audio::{self, Cue};
 use crate::container::{Container, Direction, Point};
 use crate::enemy::{self, SmallAlien};
+use crate::locale;
+use crate::particle::ParticleSystem;
 use crate::ship::Ship;
+use crate::sized_vector::SizedVector;
+use crate::sounds::SoundEvent;
+use crate::starfield::{StarDensity, Starfield};
+use crate::theme::Theme;
 use crate::weapon::Bullet;
 
+// smallest window the playfield, HUD and formation still fit in
+pub const MIN_WINDOW_WIDTH: usize = 60;
+pub const MIN_WINDOW_HEIGHT: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum GameCondition {
     Running,
     Win,
@@ -12,16 +25,84 @@ pub enum GameCondition {
     Ended,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GameMode {
+    // lives carry over and every cleared wave brings a bigger formation
+    Classic,
+    // a single life, no extra lives between waves
+    Hardcore,
+}
+
+impl GameMode {
+    pub fn all() -> Vec<GameMode> {
+        vec![GameMode::Classic, GameMode::Hardcore]
+    }
+
+    pub fn get_name(&self) -> String {
+        match self {
+            GameMode::Classic => locale::text("mode.classic"),
+            GameMode::Hardcore => locale::text("mode.hardcore"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn all() -> Vec<Difficulty> {
+        vec![Difficulty::Easy, Difficulty::Normal, Difficulty::Hard]
+    }
+
+    pub fn get_name(&self) -> String {
+        match self {
+            Difficulty::Easy => locale::text("difficulty.easy"),
+            Difficulty::Normal => locale::text("difficulty.normal"),
+            Difficulty::Hard => locale::text("difficulty.hard"),
+        }
+    }
+
+    pub fn get_enemy_move_duration(&self) -> std::time::Duration {
+        match self {
+            Difficulty::Easy => std::time::Duration::from_millis(300),
+            Difficulty::Normal => std::time::Duration::from_millis(200),
+            Difficulty::Hard => std::time::Duration::from_millis(120),
+        }
+    }
+
+    pub fn get_enemy_attack_wait_duration(&self) -> std::time::Duration {
+        match self {
+            Difficulty::Easy => std::time::Duration::from_millis(2500),
+            Difficulty::Normal => std::time::Duration::from_millis(1500),
+            Difficulty::Hard => std::time::Duration::from_millis(900),
+        }
+    }
+}
+
 pub struct Game {
+    mode: GameMode,
+    difficulty: Difficulty,
+
     score: f32,
+    high_score: f32,
     lives: usize,
     max_lives: usize,
+    wave: usize,
+
+    shots_fired: usize,
+    shots_hit: usize,
+    event_log: SizedVector<String>,
 
     enemies: Vec<SmallAlien>,
 
     window: Container,
 
     playable_area: Container,
+    side_panel: Container,
 
     enemy_rows: usize,
     enemy_cols: usize,
@@ -37,6 +118,20 @@ pub struct Game {
     last_bullet: Option<Bullet>,
 
     ship: Ship,
+
+    particles: ParticleSystem,
+    starfield: Starfield,
+    theme: Theme,
+
+    // accessibility
+    reduced_motion: bool,
+    audio_cues: bool,
+    narration_enabled: bool,
+    narration: Vec<String>,
+
+    paused_at: Option<std::time::Instant>,
+    // ticks run while not paused, sounds are logged against it
+    ticks: u64,
 }
 
 impl Game {
@@ -48,31 +143,58 @@ impl Game {
         enemy_rows: usize,
         enemy_cols: usize,
     ) -> Self {
-        let (window, playable_area) = Self::build_containers(width, height);
+        let (window, playable_area, side_panel) = Self::build_containers(width, height);
 
-        let ship = Ship::new(playable_area.top.x + 1, playable_area.bottom.y - 1, 3, 1);
+        let theme = Theme::default();
+        let ship = Ship::new(
+            playable_area.top.x + 1,
+            playable_area.bottom.y - 1,
+            3,
+            1,
+            &theme.ship,
+        );
 
         let now = std::time::Instant::now();
 
+        let difficulty = Difficulty::Normal;
+        let starfield = Starfield::new(&playable_area, StarDensity::Medium);
+
         let mut game = Self {
+            mode: GameMode::Classic,
+            difficulty,
             score,
+            high_score: score,
             lives,
             max_lives: lives,
+            wave: 1,
+            shots_fired: 0,
+            shots_hit: 0,
+            event_log: SizedVector::new(32),
             window,
             enemies: vec![],
             playable_area,
+            side_panel,
             enemy_rows,
             enemy_cols,
             enemy_direction: Direction::LEFT,
             enemy_speed: 1,
             last_bullet: None,
             enemy_last_move: now,
-            enemy_move_duration: std::time::Duration::from_millis(200),
+            enemy_move_duration: difficulty.get_enemy_move_duration(),
             ship,
             enemy_gap: 2,
             enemy_bullets: vec![],
-            enemy_attack_wait_duration: std::time::Duration::from_millis(1500),
+            enemy_attack_wait_duration: difficulty.get_enemy_attack_wait_duration(),
             last_enemy_attack_tick: now,
+            particles: ParticleSystem::new(256),
+            starfield,
+            theme,
+            reduced_motion: false,
+            audio_cues: false,
+            narration_enabled: false,
+            narration: vec![],
+            paused_at: None,
+            ticks: 0,
         };
 
         game.init();
@@ -83,6 +205,28 @@ impl Game {
         self.max_lives
     }
 
+    pub fn set_max_lives(&mut self, max_lives: usize) {
+        self.max_lives = max_lives;
+    }
+
+    pub fn get_mode(&self) -> GameMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: GameMode) {
+        self.mode = mode;
+    }
+
+    pub fn get_difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+        self.enemy_move_duration = difficulty.get_enemy_move_duration();
+        self.enemy_attack_wait_duration = difficulty.get_enemy_attack_wait_duration();
+    }
+
     pub fn set_lives(&mut self, lives: usize) {
         self.lives = lives
     }
@@ -91,7 +235,8 @@ impl Game {
         self.score = score;
     }
 
-    pub fn build_containers(width: usize, height: usize) -> (Container, Container) {
+    // splits the window into the playfield (left 70%) and the side panel HUD
+    pub fn build_containers(width: usize, height: usize) -> (Container, Container, Container) {
         let window = Container {
             top: Point { x: 0, y: 0 },
             bottom: Point {
@@ -118,14 +263,83 @@ impl Game {
             padding_vertical: 0,
         };
 
-        (window, playable_area)
+        let side_panel = Container {
+            top: Point {
+                x: playable_area.bottom.x + window.padding_horizontal,
+                y: playable_area.top.y,
+            },
+            bottom: Point {
+                x: window.bottom.x - window.padding_horizontal,
+                y: playable_area.bottom.y,
+            },
+            padding_horizontal: 2,
+            padding_vertical: 1,
+        };
+
+        (window, playable_area, side_panel)
     }
 
     pub fn set_window(&mut self, width: usize, height: usize) {
-        let (window, playable_area) = Self::build_containers(width, height);
+        let (window, playable_area, side_panel) = Self::build_containers(width, height);
 
         self.window = window;
         self.playable_area = playable_area;
+        self.side_panel = side_panel;
+
+        self.starfield.set_bounds(&self.playable_area);
+        self.fit_to_playable_area();
+    }
+
+    pub fn is_window_size_supported(width: usize, height: usize) -> bool {
+        width >= MIN_WINDOW_WIDTH && height >= MIN_WINDOW_HEIGHT
+    }
+
+    // pulls every object back inside the playfield after it changed size
+    fn fit_to_playable_area(&mut self) {
+        let area = self.playable_area.clone();
+
+        let ship_length = self.ship.get_length();
+        let ship_x = self
+            .ship
+            .get_pos()
+            .x
+            .clamp(area.top.x, area.bottom.x - ship_length);
+        let ship_y = area.bottom.y - 1;
+        let ship_container = Container::new(
+            Point {
+                x: ship_x,
+                y: ship_y,
+            },
+            Point {
+                x: ship_x + ship_length,
+                y: ship_y,
+            },
+        );
+        self.ship.set_container(&ship_container);
+
+        for e in &mut self.enemies {
+            let mut pos = e.get_pos();
+            pos.x = pos.x.clamp(area.top.x + 1, area.bottom.x - 1);
+            pos.y = pos.y.clamp(area.top.y + 1, area.bottom.y - 2);
+            e.set_pos(&pos);
+        }
+
+        let inside = |b: &Bullet| {
+            let pos = b.get_pos();
+            pos.x > area.top.x
+                && pos.x < area.bottom.x
+                && pos.y > area.top.y
+                && pos.y < area.bottom.y
+        };
+
+        if let Some(bullet) = &self.last_bullet {
+            if !inside(bullet) {
+                self.last_bullet = None;
+            }
+        }
+        self.enemy_bullets.retain(inside);
+
+        self.particles.clear();
     }
 
     pub fn set_enemy_rows_cols(&mut self, mut rows: usize, mut cols: usize) {
@@ -155,6 +369,10 @@ impl Game {
         self.window.clone()
     }
 
+    pub fn get_side_panel(&self) -> Container {
+        self.side_panel.clone()
+    }
+
     pub fn get_score(&self) -> f32 {
         self.score
     }
@@ -164,16 +382,146 @@ impl Game {
 
     pub fn add_score(&mut self, s: f32) {
         self.score += s;
+
+        if self.score > self.high_score {
+            self.high_score = self.score;
+        }
+    }
+
+    pub fn get_high_score(&self) -> f32 {
+        self.high_score
+    }
+
+    pub fn set_high_score(&mut self, high_score: f32) {
+        self.high_score = high_score;
+    }
+
+    pub fn get_wave(&self) -> usize {
+        self.wave
+    }
+
+    pub fn set_wave(&mut self, wave: usize) {
+        self.wave = wave;
+        self.cue(Cue::WaveStart);
+        self.log_event(&locale::text_with(
+            "event.wave",
+            &[("wave", wave.to_string())],
+        ));
+    }
+
+    // share of fired shots that hit an alien, between 0 and 1
+    pub fn get_accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            return 0.0;
+        }
+
+        self.shots_hit as f32 / self.shots_fired as f32
+    }
+
+    // events show up in the side panel and are narrated
+    pub fn log_event(&mut self, event: &str) {
+        self.event_log.push(event.to_string());
+        self.narrate(event);
+    }
+
+    pub fn is_reduced_motion(&self) -> bool {
+        self.reduced_motion
+    }
+
+    // no particles and no glyph animations, nothing flickers
+    pub fn set_reduced_motion(&mut self, reduced_motion: bool) {
+        self.reduced_motion = reduced_motion;
+        self.particles.set_enabled(!reduced_motion);
+        self.starfield.set_scrolling(!reduced_motion);
+    }
+
+    pub fn get_audio_cues(&self) -> bool {
+        self.audio_cues
+    }
+
+    pub fn set_audio_cues(&mut self, audio_cues: bool) {
+        self.audio_cues = audio_cues;
+    }
+
+    // the edge cue comes from the side the ship ran into
+    fn cue(&mut self, cue: Cue) {
+        if !self.audio_cues {
+            return;
+        }
+
+        let pan = match cue {
+            Cue::Edge => {
+                let ship = self.ship.get_container();
+                audio::get_pan(ship.top.x, &self.playable_area)
+            }
+            Cue::WaveStart => 0.0,
+        };
+
+        let result = audio::play_cue(cue, pan);
+        self.report_sound(result);
+    }
+
+    // a sound that failed to play only shows up in the side panel
+    fn report_sound(&mut self, result: anyhow::Result<()>) {
+        if let Err(e) = result {
+            self.event_log.push(e.to_string());
+        }
+    }
+
+    pub fn set_narration(&mut self, enabled: bool) {
+        self.narration_enabled = enabled;
+        self.narration.clear();
+    }
+
+    // queues a line for the narration stream, see `take_narration`
+    pub fn narrate(&mut self, line: &str) {
+        if self.narration_enabled {
+            self.narration.push(line.to_string());
+        }
+    }
+
+    // the narration lines queued since the last call
+    pub fn take_narration(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.narration)
+    }
+
+    pub fn get_event_log(&self) -> &SizedVector<String> {
+        &self.event_log
+    }
+
+    pub fn get_theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    // re-skins everything on screen, objects created later pick the theme up too
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.ship.set_frames(&theme.ship);
+
+        for e in &mut self.enemies {
+            e.set_frames(&theme.alien);
+        }
+
+        if let Some(bullet) = &mut self.last_bullet {
+            bullet.set_frames(&theme.bullet);
+        }
+
+        for eb in &mut self.enemy_bullets {
+            eb.set_frames(&theme.enemy_bullet);
+        }
+
+        self.particles.set_theme(&theme);
+        self.starfield.set_theme(&theme);
+        self.theme = theme;
     }
 
     pub fn init_ship(&mut self) {
-        let ship = Ship::new(
+        self.ship = Ship::new(
             self.playable_area.top.x + 1,
             self.playable_area.bottom.y - 1,
             3,
             1,
+            &self.theme.ship,
         );
-        self.ship = ship;
     }
     pub fn init_enemy(&mut self, start_x: usize, start_y: usize) {
         self.enemies.clear();
@@ -185,8 +533,7 @@ impl Game {
             for j in start_col..start_col + self.enemy_cols {
                 let x = j * self.enemy_gap;
 
-                let enemy = SmallAlien::new(x, i);
-                self.enemies.push(enemy);
+                self.enemies.push(SmallAlien::new(x, i, &self.theme.alien));
             }
         }
     }
@@ -249,6 +596,9 @@ impl Game {
             }
 
             e.set_pos(&current_pos);
+            if !self.reduced_motion {
+                e.next_frame();
+            }
         }
     }
 
@@ -256,6 +606,18 @@ impl Game {
         &self.enemies
     }
 
+    pub fn get_enemy_direction(&self) -> Direction {
+        self.enemy_direction.clone()
+    }
+
+    pub fn get_enemy_move_duration(&self) -> std::time::Duration {
+        self.enemy_move_duration
+    }
+
+    pub fn get_enemies_left(&self) -> usize {
+        self.enemies.iter().filter(|e| !e.is_destroyed()).count()
+    }
+
     pub fn enemy_attack(&mut self) {
         let mut rng = rand::thread_rng();
         let rand_index: usize = rng.gen_range(0..self.enemies.len());
@@ -271,9 +633,22 @@ impl Game {
         let last_enemy_attack_tick = now - self.last_enemy_attack_tick;
 
         if last_enemy_attack_tick >= self.enemy_attack_wait_duration {
-            let bullet = Bullet::new(enemy_fire_x, enemy_fire_y, Direction::DOWN);
+            let bullet = Bullet::new(
+                enemy_fire_x,
+                enemy_fire_y,
+                Direction::DOWN,
+                &self.theme.enemy_bullet,
+            );
             self.enemy_bullets.push(bullet);
             self.last_enemy_attack_tick = now;
+
+            let column = rand_index % self.enemy_cols.max(1) + 1;
+            let result = self.enemies[rand_index].fire(&self.playable_area);
+            self.report_sound(result);
+            self.narrate(&locale::text_with(
+                "narration.enemy_firing",
+                &[("column", column.to_string())],
+            ));
         }
     }
 
@@ -290,6 +665,7 @@ impl Game {
     fn detect_ship_collision(&mut self) {
         let mut destroyed = false;
         let mut bullet_index = 0;
+        let mut sound = Ok(());
 
         for (idx, eb) in self.enemy_bullets.iter_mut().enumerate() {
             let next_pos = eb.next_pos();
@@ -300,7 +676,7 @@ impl Game {
                     && (next_pos.x >= ship_container.top.x && next_pos.x <= ship_container.bottom.x)
                 {
                     eb.destroy();
-                    self.ship.destroy();
+                    sound = self.ship.destroy(&self.playable_area);
                     bullet_index = idx;
                     destroyed = true;
                 }
@@ -308,13 +684,26 @@ impl Game {
         }
 
         if destroyed {
+            self.particles
+                .spawn_ship_destruction(&self.ship.get_container());
             self.reduce_life();
+            self.report_sound(sound);
+            // the last life ends the game, that has its own sound
+            if self.lives > 0 {
+                self.report_sound(audio::play_event(SoundEvent::LifeLost));
+            }
+            self.log_event(&locale::text("event.ship_destroyed"));
+            self.narrate(&locale::text_with(
+                "narration.lives_left",
+                &[("lives", self.lives.to_string())],
+            ));
             self.init_ship();
             self.enemy_bullets.remove(bullet_index);
         }
     }
     fn detect_enemy_collision(&mut self) {
         let mut score = 0.0;
+        let mut sound = Ok(());
 
         if let Some(bullet) = &mut self.last_bullet {
             if bullet.is_destroyed() {
@@ -331,17 +720,33 @@ impl Game {
                 let e_pos = e.get_pos();
 
                 if e_pos.x == b_pos.x && e_pos.y == new_y {
-                    e.destroy().expect("Failed to destroy enemy");
+                    sound = e.destroy(&self.playable_area);
+                    self.particles.spawn_explosion(&e_pos);
                     bullet.destroy();
                     let points = e.get_points() as f32;
                     score += points;
+                    self.shots_hit += 1;
 
                     break;
                 }
             }
         }
 
-        self.add_score(score);
+        self.report_sound(sound);
+
+        if score > 0.0 {
+            self.add_score(score);
+            self.log_event(&locale::text_with(
+                "event.alien_down",
+                &[("points", score.to_string())],
+            ));
+
+            let left = self.enemies.iter().filter(|e| !e.is_destroyed()).count();
+            self.narrate(&locale::text_with(
+                "narration.aliens_left",
+                &[("aliens", left.to_string())],
+            ));
+        }
     }
 
     fn collision_detection(&mut self) {
@@ -354,6 +759,7 @@ impl Game {
             let next_pos = bullet.next_pos();
             if next_pos.y <= self.playable_area.top.y || next_pos.y >= self.playable_area.bottom.y {
                 bullet.destroy();
+                self.particles.spawn_impact(&bullet.get_pos());
                 self.last_bullet = None;
             } else {
                 bullet.move_tick();
@@ -361,6 +767,22 @@ impl Game {
         }
     }
 
+    fn animate(&mut self) {
+        if self.reduced_motion {
+            return;
+        }
+
+        self.ship.animate();
+
+        if let Some(bullet) = &mut self.last_bullet {
+            bullet.animate();
+        }
+
+        for eb in &mut self.enemy_bullets {
+            eb.animate();
+        }
+    }
+
     fn move_enemy_bullets(&mut self) {
         let mut destroyed_indexes = vec![];
 
@@ -368,6 +790,7 @@ impl Game {
             let next_pos = eb.next_pos();
             if next_pos.y <= self.playable_area.top.y || next_pos.y >= self.playable_area.bottom.y {
                 eb.destroy();
+                self.particles.spawn_impact(&eb.get_pos());
                 destroyed_indexes.push(i);
             } else {
                 eb.move_tick();
@@ -380,6 +803,10 @@ impl Game {
     }
 
     pub fn fire_bullet(&mut self) {
+        if self.is_paused() {
+            return;
+        }
+
         if self.last_bullet.is_none() {
             let ship_container = self.ship.get_container();
             let middle: usize = self.ship.get_width() / 2;
@@ -387,9 +814,13 @@ impl Game {
             let x = ship_container.top.x + middle;
             let y = ship_container.top.y - 1;
 
-            let bullet = Bullet::new(x, y, Direction::UP);
+            let bullet = Bullet::new(x, y, Direction::UP, &self.theme.bullet);
 
             self.last_bullet = Some(bullet);
+            self.shots_fired += 1;
+
+            let result = self.ship.fire(&self.playable_area);
+            self.report_sound(result);
         }
     }
 
@@ -434,8 +865,59 @@ impl Game {
         &self.enemy_bullets
     }
 
+    pub fn get_ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    pub fn pause(&mut self) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(std::time::Instant::now());
+        }
+    }
+
+    // every timer is pushed forward by the paused time, so marching,
+    // bullets and the attack cooldown carry on where they stopped
+    pub fn resume(&mut self) {
+        let paused_at = match self.paused_at.take() {
+            Some(t) => t,
+            None => return,
+        };
+        let paused_for = paused_at.elapsed();
+
+        self.enemy_last_move += paused_for;
+        self.last_enemy_attack_tick += paused_for;
+
+        for e in &mut self.enemies {
+            e.shift_timers(paused_for);
+        }
+
+        self.ship.shift_timers(paused_for);
+
+        if let Some(bullet) = &mut self.last_bullet {
+            bullet.shift_timers(paused_for);
+        }
+
+        for eb in &mut self.enemy_bullets {
+            eb.shift_timers(paused_for);
+        }
+
+        self.particles.shift_timers(paused_for);
+        self.starfield.shift_timers(paused_for);
+    }
+
     // keep ticking until game conditions have met
     pub fn tick(&mut self) -> GameCondition {
+        if self.is_paused() {
+            return GameCondition::Running;
+        }
+
+        self.ticks += 1;
+        audio::set_tick(self.ticks);
+
         self.move_enemy();
         self.move_bullet();
         self.collision_detection();
@@ -443,25 +925,39 @@ impl Game {
         self.enemy_attack();
         self.move_enemy_bullets();
 
+        self.animate();
+        self.particles.tick(&self.playable_area);
+        self.starfield.tick();
+
         self.has_game_ended()
     }
 
     pub fn move_ship(&mut self, direction: Direction) {
+        if self.is_paused() {
+            return;
+        }
+
         let ship_container = self.ship.get_container();
 
         if ship_container.bottom.x >= self.playable_area.bottom.x {
             if let Direction::RIGHT = direction {
-                return;
+                return self.cue(Cue::Edge);
             }
         }
 
         if ship_container.top.x <= self.playable_area.top.x {
             if let Direction::LEFT = direction {
-                return;
+                return self.cue(Cue::Edge);
             }
         }
 
         self.ship.move_ship(direction);
+
+        let x = self.ship.get_pos().x - self.playable_area.top.x;
+        self.narrate(&locale::text_with(
+            "narration.ship_at",
+            &[("x", x.to_string())],
+        ));
     }
 
     pub fn get_ship(&self) -> &Ship {
@@ -472,11 +968,55 @@ impl Game {
         &self.last_bullet
     }
 
+    pub fn get_particles(&self) -> &ParticleSystem {
+        &self.particles
+    }
+
+    pub fn get_starfield(&self) -> &Starfield {
+        &self.starfield
+    }
+
+    // the background can be turned off for slow or low-bandwidth terminals
+    pub fn set_starfield(&mut self, enabled: bool, density: StarDensity) {
+        self.starfield.set_enabled(enabled);
+        self.starfield.set_density(density);
+    }
+
+    // keeps score and lives, brings in a bigger formation for the next wave
+    pub fn next_wave(&mut self) {
+        let score = self.score;
+        let lives = self.lives;
+        let wave = self.wave;
+        let (shots_fired, shots_hit) = (self.shots_fired, self.shots_hit);
+        let (rows, cols) = self.get_enemies_rows_cols();
+
+        self.reset_game();
+
+        self.set_score(score);
+        self.shots_fired = shots_fired;
+        self.shots_hit = shots_hit;
+        if let GameMode::Hardcore = self.mode {
+            self.set_lives(lives);
+        } else {
+            self.set_lives(lives + 1);
+        }
+        self.set_wave(wave + 1);
+        self.set_enemy_rows_cols(rows + 1, cols + 1);
+    }
+
     pub fn reset_game(&mut self) {
-        self.lives = self.max_lives;
+        self.lives = match self.mode {
+            GameMode::Classic => self.max_lives,
+            GameMode::Hardcore => 1,
+        };
         self.score = 0.0;
+        self.wave = 1;
+        self.shots_fired = 0;
+        self.shots_hit = 0;
+        self.event_log.clear();
         self.last_bullet = None;
         self.enemy_bullets.clear();
+        self.particles.clear();
 
         self.init();
     }
@@ -1,12 +1,22 @@
-use crate::container::{Container, Direction, Point};
+use std::collections::HashMap;
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::container::{Container, Point};
 
 use crate::game::Game;
+use crate::locale::{self, text_width};
+use crate::palette::CellRole;
+use crate::text_processing::{render_text, BannerFont, GlyphGrid};
+use crate::theme::Theme;
 use crate::weapon::Bullet;
 
 use crate::audio;
 
 pub struct GameBuffer {
     pub grid: Vec<Vec<String>>,
+    // what every cell shows, the renderer colors cells by it
+    roles: Vec<Vec<CellRole>>,
     pub rows: usize,
     pub cols: usize,
 
@@ -21,6 +31,11 @@ pub struct GameBuffer {
     //
     playable_area: Container,
     window: Container,
+    side_panel: Container,
+    theme: Theme,
+
+    // rasterizing a banner is slow, screens redraw the same title every frame
+    banner_cache: HashMap<(String, usize, usize), GlyphGrid>,
 }
 
 impl GameBuffer {
@@ -28,9 +43,11 @@ impl GameBuffer {
         let window = game.get_window();
 
         let playable_area = game.get_playablearea();
+        let side_panel = game.get_side_panel();
 
         let mut game_buffer = GameBuffer {
             grid: vec![],
+            roles: vec![],
             rows: window.get_height(),
             cols: window.get_width(),
             enemy_rows: 5,
@@ -42,12 +59,73 @@ impl GameBuffer {
             //boundary_coordinates: (2, 2, 62, 30),
             playable_area,
             window,
+            side_panel,
+            theme: game.get_theme().clone(),
+            banner_cache: HashMap::new(),
         };
 
         game_buffer.init(game);
 
         game_buffer
     }
+
+    // a buffer that is not tied to a game layout, e.g. for the "terminal too small" screen
+    pub fn blank(cols: usize, rows: usize) -> GameBuffer {
+        let window = Container::new(Point { x: 0, y: 0 }, Point { x: cols, y: rows });
+
+        let mut game_buffer = GameBuffer {
+            grid: vec![],
+            roles: vec![],
+            rows,
+            cols,
+            enemy_rows: 0,
+            enemy_cols: 0,
+            enemy_gap: 0,
+
+            ship_length: 0,
+            ship_current_box: None,
+            playable_area: window.clone(),
+            side_panel: Container::new(Point { x: 0, y: 0 }, Point { x: 0, y: 0 }),
+            window,
+            theme: Theme::default(),
+            banner_cache: HashMap::new(),
+        };
+
+        game_buffer.init_buffer();
+        game_buffer.clear();
+
+        game_buffer
+    }
+
+    pub fn draw_too_small(&mut self, min_cols: usize, min_rows: usize) {
+        self.clear();
+
+        let lines = [
+            locale::text("too_small.title"),
+            locale::text_with(
+                "too_small.need",
+                &[
+                    ("cols", min_cols.to_string()),
+                    ("rows", min_rows.to_string()),
+                ],
+            ),
+            locale::text_with(
+                "too_small.have",
+                &[
+                    ("cols", self.cols.to_string()),
+                    ("rows", self.rows.to_string()),
+                ],
+            ),
+        ];
+
+        let mid_y = self.rows / 2;
+        let start_y = mid_y.saturating_sub(lines.len() / 2);
+
+        for (i, line) in lines.iter().enumerate() {
+            let x = self.cols.saturating_sub(text_width(line)) / 2;
+            self.write_text(x, start_y + i, line, self.cols);
+        }
+    }
     pub fn clear(&mut self) {
         for row in &mut self.grid {
             for col in row {
@@ -55,6 +133,10 @@ impl GameBuffer {
                 col.insert(0, ' ');
             }
         }
+
+        for row in &mut self.roles {
+            row.fill(CellRole::Text);
+        }
     }
 
     fn init_buffer(&mut self) {
@@ -64,8 +146,22 @@ impl GameBuffer {
                 row.push(" ".into());
             }
             self.grid.push(row);
+            self.roles.push(vec![CellRole::Text; self.cols]);
         }
     }
+
+    fn set_cell(&mut self, x: usize, y: usize, symbol: &str, role: CellRole) {
+        if y >= self.rows || x >= self.cols {
+            return;
+        }
+
+        self.grid[y][x] = symbol.to_string();
+        self.roles[y][x] = role;
+    }
+
+    pub fn get_roles(&self) -> &Vec<Vec<CellRole>> {
+        &self.roles
+    }
     fn init(&mut self, game: &Game) {
         self.init_buffer();
         self.clear();
@@ -74,6 +170,14 @@ impl GameBuffer {
         self.draw_boundary();
     }
 
+    pub fn get_theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn set_theme(&mut self, theme: &Theme) {
+        self.theme = theme.clone();
+    }
+
     pub fn get_cols(&self) -> usize {
         self.cols
     }
@@ -105,56 +209,39 @@ impl GameBuffer {
 
         for i in 0..self.window.get_height() {
             for j in 0..self.playable_area.get_width() + self.window.padding_horizontal * 2 {
-                let row = &mut self.grid[i];
-                let item = &mut row[j];
-
                 if i == self.playable_area.top.y || i == self.playable_area.bottom.y {
-                    item.clear();
-                    item.insert(0, '-');
+                    let border = self.theme.border.clone();
+                    self.set_cell(j, i, &border, CellRole::Border);
                 }
             }
         }
     }
 
+    // score on the left and lives on the right of the row above the
+    // playfield; when both don't fit the score gets cut, not the game.
+    // a missing sound device is shown left of the lives if there is room
     pub fn draw_text(&mut self, game: &Game) {
-        let score_text = format!("Score: {}", game.get_score());
-        let lives_text = format!("Lives: {}", game.get_lives());
-
-        let score_text_len = score_text.len();
-        let lives_text_len = lives_text.len();
-
-        let total_len = score_text_len + lives_text_len;
-
-        if total_len > self.playable_area.get_width() {
-            panic!(
-                "Text length exceeds total buffer cols: {}>{}",
-                total_len, self.cols
-            );
-        }
-
-        let space_len = self.playable_area.get_width() - total_len;
+        let score_text = locale::text_with("hud.score", &[("score", game.get_score().to_string())]);
+        let lives_text = locale::text_with("hud.lives", &[("lives", game.get_lives().to_string())]);
 
-        let mut game_display_text = String::new();
-        game_display_text += score_text.as_str();
-
-        for _ in 0..space_len {
-            game_display_text += " ";
-        }
+        let width = self.playable_area.get_width().min(self.cols);
+        let y = self.playable_area.top.y.saturating_sub(1);
 
-        game_display_text += lives_text.as_str();
+        let lives_width = text_width(&lives_text).min(width);
+        let score_width = width.saturating_sub(lives_width + 1);
 
-        let display_row_index = self.playable_area.top.y - 1;
-        let display_row = &mut self.grid[display_row_index];
+        self.write_text(0, y, &score_text, score_width);
+        self.write_text(width - lives_width, y, &lives_text, lives_width);
 
-        assert!(
-            game_display_text.len() <= display_row.len(),
-            "display text length greater than total buffer cols"
-        );
+        if !audio::is_available() {
+            let text = locale::text("hud.audio_unavailable");
+            let free = score_width.saturating_sub(text_width(&score_text) + 1);
+            let text_width = text_width(&text);
 
-        for (i, ch) in game_display_text.chars().enumerate() {
-            let col = &mut display_row[i];
-            col.clear();
-            col.insert(0, ch);
+            if text_width < free {
+                let x = width - lives_width - 1 - text_width;
+                self.write_text_as(x, y, &text, text_width, CellRole::Highlight);
+            }
         }
     }
 
@@ -166,7 +253,7 @@ impl GameBuffer {
 
         for i in 0..ship.get_width() {
             let new_x = ship_container.top.x + i;
-            self.grid[ship_container.top.y][new_x] = symbol.to_string();
+            self.set_cell(new_x, ship_container.top.y, symbol, CellRole::Ship);
         }
     }
 
@@ -180,9 +267,7 @@ impl GameBuffer {
             let x = contianer.top.x;
             let y = contianer.top.y;
 
-            let symbol = b.get_symbol().to_string();
-
-            self.grid[y][x] = symbol;
+            self.set_cell(x, y, b.get_symbol(), CellRole::Bullet);
         }
     }
 
@@ -196,9 +281,7 @@ impl GameBuffer {
             let x = contianer.top.x;
             let y = contianer.top.y;
 
-            let symbol = eb.get_symbol().to_string();
-
-            self.grid[y][x] = symbol;
+            self.set_cell(x, y, eb.get_symbol(), CellRole::EnemyBullet);
         }
     }
 
@@ -210,7 +293,284 @@ impl GameBuffer {
             let pos = e.get_pos();
             let symbol = e.get_symbol();
 
-            self.grid[pos.y][pos.x] = symbol.to_string();
+            self.set_cell(pos.x, pos.y, symbol, CellRole::Alien);
+        }
+    }
+
+    fn write_text(&mut self, x: usize, y: usize, text: &str, max_width: usize) {
+        self.write_text_as(x, y, text, max_width, CellRole::Text);
+    }
+
+    // writes `text` starting at (x, y), clipped to `max_width` columns; a
+    // wide character fills its cell and leaves the next one empty so the
+    // rest of the row stays aligned
+    fn write_text_as(&mut self, x: usize, y: usize, text: &str, max_width: usize, role: CellRole) {
+        if y >= self.rows {
+            return;
+        }
+
+        let end = (x + max_width).min(self.cols);
+        let mut col = x;
+
+        for ch in text.chars() {
+            let width = UnicodeWidthChar::width(ch).unwrap_or(0);
+            if width == 0 {
+                continue;
+            }
+
+            if col + width > end {
+                break;
+            }
+
+            self.set_cell(col, y, &ch.to_string(), role);
+            for filler in col + 1..col + width {
+                self.set_cell(filler, y, "", role);
+            }
+
+            col += width;
+        }
+    }
+
+    // writes `text` horizontally centered inside `area` on row `y`
+    fn write_centered(&mut self, area: &Container, y: usize, text: &str, role: CellRole) {
+        let x = area.top.x + area.get_width().saturating_sub(text_width(text)) / 2;
+
+        self.write_text_as(x, y, text, area.get_width(), role);
+    }
+
+    fn clear_area(&mut self, area: &Container) {
+        for y in area.top.y..area.bottom.y.min(self.rows) {
+            for x in area.top.x..area.bottom.x.min(self.cols) {
+                self.set_cell(x, y, " ", CellRole::Text);
+            }
+        }
+    }
+
+    // centers the grid inside `area`, set cells are drawn with `symbol`;
+    // returns the first row below the banner
+    pub fn draw_banner(&mut self, grid: &GlyphGrid, area: &Container, symbol: &str) -> usize {
+        let grid_height = grid.len();
+        let grid_width = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+
+        let start_x = area.top.x + area.get_width().saturating_sub(grid_width) / 2;
+        let start_y = area.top.y + area.get_height().saturating_sub(grid_height) / 2;
+
+        for (i, row) in grid.iter().enumerate() {
+            for (j, set) in row.iter().enumerate() {
+                let (x, y) = (start_x + j, start_y + i);
+                if *set {
+                    self.set_cell(x, y, symbol, CellRole::Banner);
+                }
+            }
+        }
+
+        start_y + grid_height
+    }
+
+    // a banner title with lines of text centered below it; the `selected`
+    // line is marked as the active menu entry
+    fn draw_screen(
+        &mut self,
+        area: &Container,
+        title: &str,
+        lines: &[String],
+        selected: Option<usize>,
+    ) {
+        self.clear_area(area);
+
+        // the banner takes up to half the area, less when the lines need the room
+        let banner_height = (area.get_height() / 2)
+            .min(area.get_height().saturating_sub(lines.len() + 3))
+            .max(1);
+        let banner_bottom = area.top.y + banner_height;
+        let banner_area = Container::new(
+            Point {
+                x: area.top.x + 2,
+                y: area.top.y + 1,
+            },
+            Point {
+                x: area.bottom.x.saturating_sub(2).max(area.top.x + 2),
+                y: banner_bottom.max(area.top.y + 1),
+            },
+        );
+
+        let key = (
+            title.to_string(),
+            banner_area.get_width(),
+            banner_area.get_height(),
+        );
+        let grid = self
+            .banner_cache
+            .entry(key)
+            .or_insert_with(|| render_text(title, BannerFont::DejaVuSans, &banner_area, 64))
+            .clone();
+
+        // the banner font has no glyphs for e.g. CJK titles, those are
+        // written as plain text instead
+        let below_banner = if grid.is_empty() {
+            let y = banner_area.top.y + banner_area.get_height() / 2;
+            self.write_centered(area, y, title, CellRole::Banner);
+            y + 1
+        } else {
+            let banner = self.theme.banner.clone();
+            self.draw_banner(&grid, &banner_area, &banner)
+        };
+
+        // long menus scroll so the selected line stays visible
+        let rows = area.bottom.y.saturating_sub(below_banner + 1);
+        let skip = match selected {
+            Some(i) if rows > 0 && i >= rows => i + 1 - rows,
+            _ => 0,
+        };
+
+        for (i, line) in lines.iter().enumerate().skip(skip) {
+            let y = below_banner + 1 + i - skip;
+            if y >= area.bottom.y {
+                break;
+            }
+
+            if Some(i) == selected {
+                self.write_centered(area, y, &format!("> {} <", line), CellRole::Highlight);
+            } else {
+                self.write_centered(area, y, line, CellRole::Text);
+            }
+        }
+    }
+
+    // screens that own the whole window, e.g. the title and the menus
+    pub fn draw_window_screen(&mut self, title: &str, lines: &[String], selected: Option<usize>) {
+        let area = self.window.clone();
+        self.draw_screen(&area, title, lines, selected);
+    }
+
+    // screens drawn over the playfield, the HUD stays visible next to them
+    pub fn draw_banner_screen(&mut self, title: &str, lines: &[String], selected: Option<usize>) {
+        let area = self.playable_area.clone();
+        let inner = Container::new(
+            Point {
+                x: area.top.x + 1,
+                y: area.top.y + 1,
+            },
+            Point {
+                x: area.bottom.x,
+                y: area.bottom.y,
+            },
+        );
+
+        self.draw_screen(&inner, title, lines, selected);
+    }
+
+    // a line of text over the lower part of the playfield, e.g. "PRESS ENTER" in the demo
+    pub fn draw_caption(&mut self, text: &str) {
+        let area = self.playable_area.clone();
+        let y = area.top.y + area.get_height() * 2 / 3;
+
+        self.write_centered(&area, y, text, CellRole::Highlight);
+    }
+
+    fn draw_side_panel(&mut self, game: &Game) {
+        let panel = self.side_panel.clone();
+        if panel.get_width() == 0 || panel.get_height() == 0 {
+            return;
+        }
+
+        let border = self.theme.border.clone();
+        for x in panel.top.x..=panel.bottom.x {
+            self.write_text_as(x, panel.top.y, &border, 1, CellRole::Border);
+            self.write_text_as(x, panel.bottom.y, &border, 1, CellRole::Border);
+        }
+
+        let content = panel.inner();
+        let width = content.get_width();
+
+        let life = self.theme.life.as_str();
+        let lives = (0..game.get_lives())
+            .map(|_| life)
+            .collect::<Vec<&str>>()
+            .join(" ");
+
+        let mut lines = vec![
+            locale::text_with(
+                "hud.hi_score",
+                &[("score", game.get_high_score().to_string())],
+            ),
+            locale::text_with("hud.wave", &[("wave", game.get_wave().to_string())]),
+            String::new(),
+            locale::text("hud.lives_title"),
+            lives,
+            String::new(),
+            locale::text_with(
+                "hud.weapon",
+                &[("weapon", locale::text(game.get_ship().get_weapon()))],
+            ),
+            locale::text_with(
+                "hud.accuracy",
+                &[("accuracy", format!("{:.0}", game.get_accuracy() * 100.0))],
+            ),
+            String::new(),
+            locale::text("hud.events"),
+        ];
+
+        // the event log scrolls, only the newest entries that fit are shown
+        let log_rows = content.get_height().saturating_sub(lines.len());
+        let events = game.get_event_log();
+        let skip = events.len().saturating_sub(log_rows);
+        for event in events.into_iter().skip(skip) {
+            lines.push(format!(" {}", event));
+        }
+
+        for (i, line) in lines.iter().enumerate().take(content.get_height()) {
+            self.write_text(content.top.x, content.top.y + i, line, width);
+        }
+    }
+
+    // stars and decorations only show inside the playfield, in the cells
+    // nothing else is drawn to
+    fn draw_background(&mut self, game: &Game) {
+        let starfield = game.get_starfield();
+        if !starfield.is_enabled() {
+            return;
+        }
+
+        let area = self.playable_area.clone();
+        let inside = |x: usize, y: usize| {
+            x > area.top.x && x < area.bottom.x && y > area.top.y && y < area.bottom.y
+        };
+
+        for d in starfield.get_decorations() {
+            for (i, row) in d.get_rows().iter().enumerate() {
+                let y = d.get_y() + i as isize;
+                if y < 0 {
+                    continue;
+                }
+
+                for (j, ch) in row.chars().enumerate() {
+                    let (x, y) = (d.get_x() + j, y as usize);
+                    if ch != ' ' && inside(x, y) {
+                        self.set_cell(x, y, &ch.to_string(), CellRole::Background);
+                    }
+                }
+            }
+        }
+
+        for star in starfield.get_stars() {
+            let pos = star.get_pos();
+            if inside(pos.x, pos.y) {
+                self.set_cell(
+                    pos.x,
+                    pos.y,
+                    starfield.get_glyph(star),
+                    CellRole::Background,
+                );
+            }
+        }
+    }
+
+    fn draw_particles(&mut self, game: &Game) {
+        for p in game.get_particles().get_particles() {
+            if let Some(pos) = p.get_pos() {
+                self.set_cell(pos.x, pos.y, p.get_symbol(), CellRole::Particle);
+            }
         }
     }
 
@@ -219,6 +579,11 @@ impl GameBuffer {
         self.draw_text(game);
 
         self.draw_boundary();
+        self.draw_side_panel(game);
+
+        // background and particles go first so gameplay objects are drawn over them
+        self.draw_background(game);
+        self.draw_particles(game);
 
         self.draw_ship(game);
 
@@ -1,3 +1,4 @@
+use crate::animation::Animation;
 use crate::container::{Container, Point};
 
 #[derive(Debug, Clone)]
@@ -5,6 +6,8 @@ pub struct GameObject {
     container: Container,
     symbol: String,
     destroyed: bool,
+
+    animation: Option<Animation>,
 }
 
 impl GameObject {
@@ -13,6 +16,7 @@ impl GameObject {
             container,
             symbol: symbol.to_string(),
             destroyed: false,
+            animation: None,
         }
     }
 
@@ -36,9 +40,55 @@ impl GameObject {
         self.destroyed = true;
     }
     pub fn get_symbol(&self) -> &str {
+        if let Some(animation) = &self.animation {
+            return animation.get_frame();
+        }
+
         &self.symbol
     }
 
+    pub fn set_animation(&mut self, animation: Animation) {
+        self.animation = Some(animation);
+    }
+
+    // re-skins the object, an animation keeps its trigger but gets the new frames
+    pub fn set_frames(&mut self, frames: &[String]) {
+        let first = match frames.first() {
+            Some(first) => first,
+            None => return,
+        };
+        self.symbol = first.clone();
+
+        if let Some(animation) = &self.animation {
+            let frames: Vec<&str> = frames.iter().map(|f| f.as_str()).collect();
+            self.animation = Some(Animation::new(&frames, animation.get_trigger()));
+        }
+    }
+
+    pub fn get_animation(&self) -> &Option<Animation> {
+        &self.animation
+    }
+
+    // time based frame advance, called once per game tick
+    pub fn animate(&mut self) {
+        if let Some(animation) = &mut self.animation {
+            animation.tick();
+        }
+    }
+
+    pub fn shift_timers(&mut self, by: std::time::Duration) {
+        if let Some(animation) = &mut self.animation {
+            animation.shift_timers(by);
+        }
+    }
+
+    // event based frame advance, e.g. an invader taking a march step
+    pub fn next_frame(&mut self) {
+        if let Some(animation) = &mut self.animation {
+            animation.next_frame();
+        }
+    }
+
     pub fn set_pos(&mut self, point: &Point) {
         self.container.top = point.clone();
         self.container.bottom = point.clone();
@@ -0,0 +1,168 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow as error, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::config_dir;
+use crate::game::GameMode;
+
+const HIGH_SCORE_FILE: &str = "highscores.json";
+pub const INITIALS_LEN: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    pub initials: String,
+    pub score: f32,
+    pub wave: usize,
+    pub date: String,
+    pub mode: GameMode,
+}
+
+// best first, never longer than `max_entries`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScoreTable {
+    entries: Vec<HighScoreEntry>,
+    max_entries: usize,
+}
+
+impl HighScoreTable {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            entries: vec![],
+            max_entries,
+        }
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(HIGH_SCORE_FILE))
+    }
+
+    pub fn load(max_entries: usize) -> Result<Self> {
+        let path = match Self::path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Self::new(max_entries)),
+        };
+
+        let data = std::fs::read_to_string(path)?;
+        let mut table: Self = serde_json::from_str(&data)?;
+        table.max_entries = max_entries;
+        table.entries.truncate(max_entries);
+
+        Ok(table)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path().ok_or(error!("No config directory found"))?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    pub fn get_entries(&self) -> &Vec<HighScoreEntry> {
+        &self.entries
+    }
+
+    pub fn get_best(&self) -> Option<f32> {
+        self.entries.first().map(|e| e.score)
+    }
+
+    pub fn qualifies(&self, score: f32) -> bool {
+        if score <= 0.0 {
+            return false;
+        }
+
+        if self.entries.len() < self.max_entries {
+            return true;
+        }
+
+        match self.entries.last() {
+            Some(last) => score > last.score,
+            None => true,
+        }
+    }
+
+    // returns the rank (0 based) the entry landed on
+    pub fn insert(&mut self, entry: HighScoreEntry) -> usize {
+        let rank = self
+            .entries
+            .iter()
+            .position(|e| entry.score > e.score)
+            .unwrap_or(self.entries.len());
+
+        self.entries.insert(rank, entry);
+        self.entries.truncate(self.max_entries);
+
+        rank
+    }
+}
+
+// arcade style: Up/Down cycle the letter under the cursor, Left/Right move it
+#[derive(Debug, Clone)]
+pub struct InitialsEntry {
+    letters: [char; INITIALS_LEN],
+    cursor: usize,
+}
+
+impl Default for InitialsEntry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InitialsEntry {
+    pub fn new() -> Self {
+        Self {
+            letters: ['A'; INITIALS_LEN],
+            cursor: 0,
+        }
+    }
+
+    pub fn get_initials(&self) -> String {
+        self.letters.iter().collect()
+    }
+
+    pub fn get_cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn next_letter(&mut self) {
+        let letter = &mut self.letters[self.cursor];
+        *letter = if *letter == 'Z' {
+            'A'
+        } else {
+            (*letter as u8 + 1) as char
+        };
+    }
+
+    pub fn previous_letter(&mut self) {
+        let letter = &mut self.letters[self.cursor];
+        *letter = if *letter == 'A' {
+            'Z'
+        } else {
+            (*letter as u8 - 1) as char
+        };
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(INITIALS_LEN - 1);
+    }
+
+    // typing a letter sets it and moves on to the next slot
+    pub fn type_letter(&mut self, ch: char) {
+        if !ch.is_ascii_alphabetic() {
+            return;
+        }
+
+        self.letters[self.cursor] = ch.to_ascii_uppercase();
+        self.move_right();
+    }
+}
@@ -3,6 +3,7 @@ use crossterm::{execute, ExecutableCommand};
 use crossterm::event::{poll, read, Event, KeyCode, KeyEvent};
 
 use crate::audio;
+use crate::config::KeyBindings;
 use crate::container::{Container, Direction, Point};
 use crate::game::Game;
 use crate::game_buffer::GameBuffer;
@@ -10,10 +11,71 @@ use crate::renderer::TerminalRenderer;
 
 use anyhow::{anyhow as error, Result};
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Pause,
+    Screenshot,
+    Record,
+    Mute,
+}
+
+// "Left", "Space", "Enter", "F5" or a single character such as "p"
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    let code = match name {
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Space" => KeyCode::Char(' '),
+        "Enter" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Tab" => KeyCode::Tab,
+        "Backspace" => KeyCode::Backspace,
+        _ => {
+            if let Some(n) = name.strip_prefix('F').and_then(|n| n.parse().ok()) {
+                return Some(KeyCode::F(n));
+            }
+
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => KeyCode::Char(ch),
+                _ => return None,
+            }
+        }
+    };
+
+    Some(code)
+}
+
+pub fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::Char(ch) => ch.to_string(),
+        _ => "?".to_string(),
+    }
+}
+
 pub struct KeyboardHandler {
     wait: u64,
     step: isize,
     ship_position: usize,
+
+    bindings: Vec<(KeyCode, Command)>,
+
+    // latest terminal size (cols, rows) reported by a resize event
+    resize: Option<(usize, usize)>,
 }
 
 impl KeyboardHandler {
@@ -21,11 +83,16 @@ impl KeyboardHandler {
         terminal.disable_cursor();
         terminal.enable_raw_mode();
 
-        KeyboardHandler {
+        let mut handler = KeyboardHandler {
             wait: 1,
             step: 1,
             ship_position: 0,
-        }
+            bindings: vec![],
+            resize: None,
+        };
+
+        handler.set_bindings(&KeyBindings::default());
+        handler
     }
 
     pub fn set_wait(&mut self, wait: u64) {
@@ -36,29 +103,83 @@ impl KeyboardHandler {
         self.step = step
     }
 
-    pub fn handle(&mut self, game: &mut Game) -> Option<KeyEvent> {
-        if let Ok(v) = self.read_keyboard_event() {
-            if let KeyCode::Char(' ') = v.code {
-                game.fire_bullet();
-            }
+    // the next key press without acting on it, for menus and other screens
+    pub fn read(&mut self) -> Option<KeyEvent> {
+        self.read_keyboard_event().ok()
+    }
 
-            if let KeyCode::Left = v.code {
-                game.move_ship(Direction::LEFT);
-            }
+    // names that don't parse keep the default binding for that command
+    pub fn set_bindings(&mut self, bindings: &KeyBindings) {
+        let defaults = KeyBindings::default();
+        let pairs = [
+            (&bindings.left, &defaults.left, Command::MoveLeft),
+            (&bindings.right, &defaults.right, Command::MoveRight),
+            (&bindings.fire, &defaults.fire, Command::Fire),
+            (&bindings.pause, &defaults.pause, Command::Pause),
+            (
+                &bindings.screenshot,
+                &defaults.screenshot,
+                Command::Screenshot,
+            ),
+            (&bindings.record, &defaults.record, Command::Record),
+            (&bindings.mute, &defaults.mute, Command::Mute),
+        ];
 
-            if let KeyCode::Right = v.code {
-                game.move_ship(Direction::RIGHT);
-            }
+        self.bindings = pairs
+            .iter()
+            .filter_map(|(name, default, command)| {
+                parse_key(name)
+                    .or(parse_key(default))
+                    .map(|code| (code, *command))
+            })
+            .collect();
+    }
+
+    pub fn get_command(&self, v: &KeyEvent) -> Option<Command> {
+        self.bindings
+            .iter()
+            .find(|(code, _)| *code == v.code)
+            .map(|(_, command)| *command)
+    }
+
+    // gameplay commands act on the game, the rest are up to the caller
+    pub fn apply_command(&mut self, game: &mut Game, command: Command) {
+        match command {
+            Command::MoveLeft => game.move_ship(Direction::LEFT),
+            Command::MoveRight => game.move_ship(Direction::RIGHT),
+            Command::Fire => game.fire_bullet(),
+            Command::Pause | Command::Screenshot | Command::Record | Command::Mute => (),
+        }
+    }
+
+    // gameplay actions bound to the key
+    pub fn apply(&mut self, game: &mut Game, v: &KeyEvent) {
+        if let Some(command) = self.get_command(v) {
+            self.apply_command(game, command);
+        }
+    }
+
+    pub fn handle(&mut self, game: &mut Game) -> Option<KeyEvent> {
+        if let Ok(v) = self.read_keyboard_event() {
+            self.apply(game, &v);
 
             return Some(v);
         }
 
         None
     }
-    fn read_keyboard_event(&self) -> Result<crossterm::event::KeyEvent> {
+    pub fn take_resize(&mut self) -> Option<(usize, usize)> {
+        self.resize.take()
+    }
+
+    fn read_keyboard_event(&mut self) -> Result<crossterm::event::KeyEvent> {
         if poll(std::time::Duration::from_millis(self.wait))? {
-            if let Event::Key(ke) = read()? {
-                return Ok(ke);
+            match read()? {
+                Event::Key(ke) => return Ok(ke),
+                Event::Resize(cols, rows) => {
+                    self.resize = Some((cols as usize, rows as usize));
+                }
+                _ => (),
             }
         }
 
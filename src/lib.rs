@@ -2,6 +2,18 @@ pub mod sized_vector;
 
 pub mod audio;
 
+pub mod audio_backend;
+
+pub mod music;
+
+pub mod synth;
+
+pub mod sounds;
+
+pub mod config;
+
+pub mod animation;
+
 pub mod enemy;
 
 pub mod container;
@@ -18,6 +30,34 @@ pub mod game_buffer;
 
 pub mod keyboard;
 
+pub mod screen;
+
+pub mod settings;
+
+pub mod highscore;
+
+pub mod theme;
+
+pub mod locale;
+
+pub mod palette;
+
+pub mod narration;
+
+pub mod bot;
+
 pub mod ship;
 
+pub mod particle;
+
+pub mod starfield;
+
 pub mod text_processing;
+
+pub mod screenshot;
+
+pub mod recorder;
+
+pub mod timestamp;
+
+pub mod log;
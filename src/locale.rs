@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use anyhow::{anyhow as error, Result};
+use unicode_width::UnicodeWidthStr;
+
+pub const AUTO: &str = "auto";
+const FALLBACK: &str = "en";
+
+// every bundled catalog, English doubles as the fallback for missing keys
+const CATALOGS: [(&str, &str); 4] = [
+    ("en", include_str!("../assets/locales/en.json")),
+    ("de", include_str!("../assets/locales/de.json")),
+    ("es", include_str!("../assets/locales/es.json")),
+    ("ja", include_str!("../assets/locales/ja.json")),
+];
+
+struct Catalog {
+    language: String,
+    messages: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+static CATALOG: RwLock<Option<Catalog>> = RwLock::new(None);
+
+fn parse(language: &str) -> Result<HashMap<String, String>> {
+    let data = CATALOGS
+        .iter()
+        .find(|(code, _)| *code == language)
+        .map(|(_, data)| *data)
+        .ok_or(error!("Unknown language: {}", language))?;
+
+    Ok(serde_json::from_str(data)?)
+}
+
+// "auto" followed by the codes of the bundled languages
+pub fn languages() -> Vec<String> {
+    let mut languages = vec![AUTO.to_string()];
+    languages.extend(CATALOGS.iter().map(|(code, _)| code.to_string()));
+    languages
+}
+
+// the language's own name, e.g. "Deutsch" for "de"
+pub fn language_name(language: &str) -> String {
+    parse(language)
+        .ok()
+        .and_then(|messages| messages.get("language.name").cloned())
+        .unwrap_or(language.to_string())
+}
+
+// the first supported language from LC_ALL, LC_MESSAGES or LANG,
+// e.g. "de_DE.UTF-8" picks "de"
+pub fn detect() -> String {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+
+    let language = locale
+        .split(['_', '.', '@', '-'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+
+    if CATALOGS.iter().any(|(code, _)| *code == language) {
+        language
+    } else {
+        FALLBACK.to_string()
+    }
+}
+
+pub fn set_language(language: &str) -> Result<()> {
+    let language = match language {
+        AUTO => detect(),
+        _ => language.to_string(),
+    };
+
+    let catalog = Catalog {
+        messages: parse(&language)?,
+        fallback: parse(FALLBACK)?,
+        language,
+    };
+
+    *CATALOG.write().unwrap() = Some(catalog);
+
+    Ok(())
+}
+
+pub fn get_language() -> String {
+    match &*CATALOG.read().unwrap() {
+        Some(catalog) => catalog.language.clone(),
+        None => FALLBACK.to_string(),
+    }
+}
+
+// the message for `key` in the current language, English when the
+// language lacks it and the key itself when no catalog knows it
+pub fn text(key: &str) -> String {
+    if CATALOG.read().unwrap().is_none() {
+        // only fails if the bundled English catalog is broken
+        let _ = set_language(FALLBACK);
+    }
+
+    let catalog = CATALOG.read().unwrap();
+    let catalog = match &*catalog {
+        Some(catalog) => catalog,
+        None => return key.to_string(),
+    };
+
+    catalog
+        .messages
+        .get(key)
+        .or(catalog.fallback.get(key))
+        .cloned()
+        .unwrap_or(key.to_string())
+}
+
+// `text` with every "{name}" placeholder replaced by its value
+pub fn text_with(key: &str, args: &[(&str, String)]) -> String {
+    let mut message = text(key);
+
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), value);
+    }
+
+    message
+}
+
+// terminal columns taken by `text`, CJK characters take two
+pub fn text_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+// pads `text` with spaces to `width` columns, for lining up table columns
+pub fn pad_to_width(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(text_width(text));
+    format!("{}{}", text, " ".repeat(padding))
+}
@@ -0,0 +1,33 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::{anyhow as error, Result};
+
+use crate::config::config_dir;
+use crate::timestamp::Timestamp;
+
+const LOG_FILE: &str = "invader.log";
+
+// problems the player doesn't need to see in the game, e.g. why there is
+// no sound; the terminal is in raw mode, so they can't go to stdout
+pub fn path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(LOG_FILE))
+}
+
+// appends one timestamped line
+pub fn write(message: &str) -> Result<()> {
+    let path = path().ok_or(error!("No config directory found"))?;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let now = Timestamp::now();
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{} {} {}", now.date(), now.time(), message)?;
+
+    Ok(())
+}
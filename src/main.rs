@@ -1,150 +1,1047 @@
+use std::path::{Path, PathBuf};
+
 use anyhow::{anyhow as error, Result};
 use invader::{
-    audio,
-    container::{Container, Point},
-    game::{Game, GameCondition},
+    audio::{self, Channel},
+    audio_backend::NullBackend,
+    bot::Bot,
+    config::Config,
+    enemy::SmallAlien,
+    game::{Difficulty, Game, GameCondition, GameMode, MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH},
     game_buffer::GameBuffer,
-    keyboard::KeyboardHandler,
+    highscore::{HighScoreEntry, HighScoreTable, InitialsEntry},
+    keyboard::{Command, KeyboardHandler},
+    locale,
+    music::{self, MusicPlayer, Track},
+    narration::Narrator,
+    recorder::{Recorder, Replay},
     renderer::TerminalRenderer,
+    screen::{AttractPhase, Menu, Screen},
+    screenshot::Screenshot,
+    settings::SettingsMenu,
+    sounds::{SoundEvent, SoundManifest},
+    theme::Theme,
+    timestamp::Timestamp,
 };
 
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent};
+
+const HIGH_SCORE_ENTRIES: usize = 10;
+
+const WAVE_INTRO_DURATION: std::time::Duration = std::time::Duration::from_millis(2000);
+
+// aliens left when the march gives way to the boss theme
+const BOSS_ALIENS: usize = 3;
+
+// idle time on the title screen before the attract mode starts
+const ATTRACT_DELAY: std::time::Duration = std::time::Duration::from_secs(15);
+const DEMO_DURATION: std::time::Duration = std::time::Duration::from_secs(30);
+// how long the high scores and the point legend stay up
+const ATTRACT_PAGE_DURATION: std::time::Duration = std::time::Duration::from_secs(8);
+
+struct Options {
+    screenshot_dir: String,
+
+    record: bool,
+    record_fps: u32,
+    record_dir: String,
+    render_replay: Option<String>,
+
+    // overrides the theme from the config without saving it
+    theme: Option<String>,
+
+    // file or pipe for the narration stream
+    narrate: Option<String>,
+
+    // silent null audio backend, for headless runs
+    no_audio: bool,
+}
+
+impl Options {
+    fn from_args() -> Result<Self> {
+        let mut options = Self {
+            screenshot_dir: "screenshots".to_string(),
+            record: false,
+            record_fps: 10,
+            record_dir: "recordings".to_string(),
+            render_replay: None,
+            theme: None,
+            narrate: None,
+            no_audio: false,
+        };
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--screenshot-dir" => {
+                    options.screenshot_dir = args
+                        .next()
+                        .ok_or(error!("--screenshot-dir expects a directory"))?;
+                }
+                "--record" => options.record = true,
+                "--record-fps" => {
+                    options.record_fps = args
+                        .next()
+                        .ok_or(error!("--record-fps expects a number"))?
+                        .parse()?;
+                }
+                "--record-dir" => {
+                    options.record_dir = args
+                        .next()
+                        .ok_or(error!("--record-dir expects a directory"))?;
+                }
+                "--render-replay" => {
+                    options.render_replay = Some(
+                        args.next()
+                            .ok_or(error!("--render-replay expects a replay file"))?,
+                    );
+                }
+                "--theme" => {
+                    options.theme = Some(args.next().ok_or(error!("--theme expects a name"))?);
+                }
+                "--narrate" => {
+                    options.narrate = Some(args.next().ok_or(error!("--narrate expects a file"))?);
+                }
+                "--no-audio" => options.no_audio = true,
+                _ => return Err(error!("Unknown argument: {}", arg)),
+            }
+        }
+
+        Ok(options)
+    }
+}
 
 struct GameManager {
     game: Game,
     tr: TerminalRenderer,
     key_handler: KeyboardHandler,
     gb: GameBuffer,
+    screenshot: Screenshot,
+    recorder: Recorder,
+    gif_jobs: Vec<std::thread::JoinHandle<Result<PathBuf>>>,
+    narrator: Option<Narrator>,
+    // what was last narrated for the current screen
+    last_description: String,
 
-    raw_toogle: bool,
+    config: Config,
+    theme_override: Option<String>,
+    settings: SettingsMenu,
+    high_scores: HighScoreTable,
+    initials: InitialsEntry,
+
+    screen: Screen,
+    screen_started: std::time::Instant,
+    main_menu: Menu,
+    mode_menu: Menu,
+    pause_menu: Menu,
+    game_condition: GameCondition,
+
+    // attract mode, the demo runs on its own game so the player's stays untouched
+    attract_phase: AttractPhase,
+    demo: Option<Game>,
+    bot: Bot,
+
+    music: MusicPlayer,
+
+    too_small: bool,
 }
 
 impl GameManager {
-    pub fn new(width: usize, height: usize) -> Self {
+    pub fn new(options: &Options) -> Result<Self> {
+        let (width, height) = TerminalRenderer::terminal_size()?;
+        let too_small = !Game::is_window_size_supported(width, height);
+
         let mut game = Game::default();
-        game.set_window(width, height);
+        game.set_window(width.max(MIN_WINDOW_WIDTH), height.max(MIN_WINDOW_HEIGHT));
+
+        let config = match Config::load() {
+            Ok(config) => config,
+            Err(e) => {
+                game.log_event(&error_event("event.config_ignored", &e));
+                Config::default()
+            }
+        };
+
+        let high_scores = match HighScoreTable::load(HIGH_SCORE_ENTRIES) {
+            Ok(table) => table,
+            Err(e) => {
+                game.log_event(&error_event("event.high_scores_ignored", &e));
+                HighScoreTable::new(HIGH_SCORE_ENTRIES)
+            }
+        };
+        game.set_high_score(high_scores.get_best().unwrap_or(0.0));
+
+        let narrator = match &options.narrate {
+            Some(target) => Some(Narrator::new(target)?),
+            None => None,
+        };
+        game.set_narration(narrator.is_some());
 
         let mut tr = TerminalRenderer::new(&game);
         let mut gb = GameBuffer::new(&game);
         let mut key_handler = KeyboardHandler::new(&mut tr);
 
-        Self {
+        let mut game_manager = Self {
             game,
             tr,
             key_handler,
             gb,
-            raw_toogle: true,
+            screenshot: Screenshot::new(&options.screenshot_dir),
+            recorder: Recorder::new(&options.record_dir, options.record_fps),
+            gif_jobs: vec![],
+            narrator,
+            last_description: String::new(),
+            settings: SettingsMenu::new(&config),
+            config,
+            theme_override: options.theme.clone(),
+            high_scores,
+            initials: InitialsEntry::new(),
+            screen: Screen::Title,
+            screen_started: std::time::Instant::now(),
+            main_menu: Menu::new(&[
+                "menu.play",
+                "menu.mode",
+                "menu.high_scores",
+                "menu.settings",
+                "menu.quit",
+            ]),
+            mode_menu: Menu::new(&["mode.classic", "mode.hardcore"]),
+            pause_menu: Menu::new(&["pause.resume", "pause.restart", "pause.quit"]),
+            game_condition: GameCondition::Ended,
+            attract_phase: AttractPhase::Demo,
+            demo: None,
+            bot: Bot::new(std::time::Duration::from_millis(80)),
+            music: MusicPlayer::new(),
+            too_small,
+        };
+
+        game_manager.apply_config();
+
+        if options.record {
+            game_manager.toggle_recording();
         }
+
+        if too_small {
+            game_manager.resize(width, height)?;
+        }
+
+        Ok(game_manager)
     }
 
-    pub fn get_game(&mut self) -> &mut Game {
-        &mut self.game
+    // rebuilds the layout for the new terminal size, or shows the
+    // "terminal too small" screen when the game can't fit
+    fn resize(&mut self, width: usize, height: usize) -> Result<()> {
+        self.too_small = !Game::is_window_size_supported(width, height);
+        self.tr.clear_screen()?;
+
+        if self.too_small {
+            self.game.pause();
+            self.gb = GameBuffer::blank(width, height);
+            self.gb.draw_too_small(MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT);
+            self.tr.draw(&self.gb)?;
+
+            return Ok(());
+        }
+
+        self.game.set_window(width, height);
+        self.tr.set_window_container(&self.game.get_window());
+
+        if let Some(demo) = &mut self.demo {
+            demo.set_window(width, height);
+        }
+        self.gb = GameBuffer::new(&self.game);
+
+        if let Screen::Playing = self.screen {
+            self.game.resume();
+        }
+
+        Ok(())
     }
 
-    fn game_loop(&mut self) -> Result<GameCondition> {
-        let mut game_condition = GameCondition::Ended;
+    fn apply_config(&mut self) {
+        let config = &self.config;
+
+        self.key_handler.set_bindings(&config.key_bindings);
+        self.game.set_difficulty(config.difficulty);
+        self.game.set_max_lives(config.starting_lives.max(1));
+        audio::set_volume(config.volume);
+        audio::set_channel_volume(Channel::Sfx, config.sfx_volume);
+        audio::set_channel_volume(Channel::Music, config.music_volume);
+        audio::set_muted(config.muted);
+        audio::set_stereo(config.stereo);
+        audio::set_synth_overrides(&config.synth);
 
-        loop {
-            let key_event = self.key_handler.handle(&mut self.game);
+        let mut manifest = SoundManifest::default();
+        for e in manifest.apply_overrides(&config.sounds) {
+            self.game.log_event(&error_event("event.sound_ignored", &e));
+        }
+        audio::set_sound_manifest(manifest);
+
+        for e in audio::preload() {
+            self.game.log_event(&error_event("event.sound_missing", &e));
+        }
 
-            game_condition = self.game.tick();
+        let music_file = Some(config.music_file.clone()).filter(|file| !file.is_empty());
+        self.music.set_file(music_file);
 
-            self.gb.draw(&self.game);
+        self.tr.set_palette(config.palette);
+        self.recorder.set_palette(config.palette);
+        self.game.set_reduced_motion(config.reduced_motion);
+        self.game.set_audio_cues(config.audio_cues);
+        self.game
+            .set_starfield(config.starfield, config.star_density);
 
-            self.tr.draw(&self.gb)?;
+        if let Err(e) = locale::set_language(&config.language) {
+            self.game
+                .log_event(&error_event("event.language_ignored", &e));
+        }
+
+        let theme_name = self.theme_override.as_deref().unwrap_or(&config.theme);
+        match Theme::load(theme_name) {
+            Ok(theme) => {
+                self.gb.set_theme(&theme);
+                self.game.set_theme(theme);
+            }
+            Err(e) => self.game.log_event(&error_event("event.theme_ignored", &e)),
+        }
+    }
+
+    fn take_screenshot(&mut self) {
+        match self.screenshot.save(&self.gb, self.tr.get_palette()) {
+            Ok(path) => self.game.log_event(&path_event("event.screenshot", &path)),
+            Err(e) => self
+                .game
+                .log_event(&error_event("event.screenshot_failed", &e)),
+        }
+    }
 
-            if let GameCondition::Win | GameCondition::Loss | GameCondition::Ended = game_condition
-            {
-                break;
+    fn toggle_recording(&mut self) {
+        if !self.recorder.is_recording() {
+            match self.recorder.start() {
+                Ok(()) => self
+                    .game
+                    .log_event(&locale::text("event.recording_started")),
+                Err(e) => self
+                    .game
+                    .log_event(&error_event("event.recording_failed", &e)),
             }
+            return;
+        }
+
+        match self.recorder.stop() {
+            Ok(job) => {
+                self.gif_jobs.push(job);
+                self.game
+                    .log_event(&locale::text("event.recording_stopped"));
+            }
+            Err(e) => self
+                .game
+                .log_event(&error_event("event.recording_failed", &e)),
+        }
+    }
+
+    // narrates a screen once when it shows up or its selection changes
+    fn describe_screen(&mut self, title: &str, lines: &[String], selected: Option<usize>) {
+        let description = match selected.and_then(|i| lines.get(i)) {
+            Some(item) => format!("{}: {}", title, item),
+            None => {
+                let mut parts = vec![title];
+                parts.extend(lines.iter().map(|l| l.trim()).filter(|l| !l.is_empty()));
+                parts.join(". ")
+            }
+        };
+
+        if description != self.last_description {
+            self.game.narrate(&description);
+            self.last_description = description;
+        }
+    }
+
+    fn show_window_screen(&mut self, title: &str, lines: &[String], selected: Option<usize>) {
+        self.gb.draw_window_screen(title, lines, selected);
+        self.describe_screen(title, lines, selected);
+    }
+
+    fn show_banner_screen(&mut self, title: &str, lines: &[String], selected: Option<usize>) {
+        self.gb.draw_banner_screen(title, lines, selected);
+        self.describe_screen(title, lines, selected);
+    }
+
+    fn flush_narration(&mut self) {
+        let lines = self.game.take_narration();
+
+        let narrator = match &mut self.narrator {
+            Some(narrator) => narrator,
+            None => return,
+        };
+
+        for line in lines {
+            if let Err(e) = narrator.say(&line) {
+                self.narrator = None;
+                self.game.set_narration(false);
+                self.game
+                    .log_event(&error_event("event.narration_failed", &e));
+                return;
+            }
+        }
+    }
+
+    fn poll_gif_jobs(&mut self) {
+        let (finished, pending) = std::mem::take(&mut self.gif_jobs)
+            .into_iter()
+            .partition(|job| job.is_finished());
+        self.gif_jobs = pending;
+
+        for job in finished {
+            match job.join() {
+                Ok(Ok(path)) => self.game.log_event(&path_event("event.gif", &path)),
+                Ok(Err(e)) => self.game.log_event(&error_event("event.gif_failed", &e)),
+                Err(_) => self.game.log_event(&locale::text("event.gif_crashed")),
+            }
+        }
+    }
+
+    // stops a running recording and waits for every GIF to be written
+    fn finish_recordings(&mut self) {
+        if self.recorder.is_recording() {
+            self.toggle_recording();
+        }
+
+        for job in self.gif_jobs.drain(..) {
+            let _ = job.join();
+        }
+    }
+
+    // the game clock only runs while its screen is showing
+    fn set_screen(&mut self, screen: Screen) {
+        self.screen = screen;
+        self.screen_started = std::time::Instant::now();
+        self.last_description.clear();
+
+        if screen != Screen::Attract {
+            self.set_demo(None);
+        }
+
+        if let Screen::Playing = screen {
+            self.game.resume();
+        } else {
+            self.game.pause();
+        }
+    }
+
+    fn start_demo(&mut self) {
+        let window = self.game.get_window();
+
+        let mut demo = Game::default();
+        demo.set_window(window.get_width(), window.get_height());
+        demo.set_theme(self.game.get_theme().clone());
+        demo.set_reduced_motion(self.game.is_reduced_motion());
+        demo.set_starfield(self.config.starfield, self.config.star_density);
+        demo.set_difficulty(Difficulty::Normal);
+        demo.set_high_score(self.game.get_high_score());
+        demo.set_enemy_rows_cols(self.config.formation_rows, self.config.formation_cols);
+
+        self.set_demo(Some(demo));
+    }
+
+    // the demo plays without sound effects, so an idle title screen stays
+    // quiet; they are back once the demo is gone
+    fn set_demo(&mut self, demo: Option<Game>) {
+        audio::set_events_enabled(demo.is_none());
+        self.demo = demo;
+    }
+
+    fn set_attract_phase(&mut self, phase: AttractPhase) {
+        self.attract_phase = phase;
+        self.screen_started = std::time::Instant::now();
+        self.last_description.clear();
+
+        if let AttractPhase::Demo = phase {
+            self.start_demo();
+        } else {
+            self.set_demo(None);
+        }
+    }
+
+    fn update_attract(&mut self, code: Option<KeyCode>) {
+        if let Some(code) = code {
+            return match code {
+                KeyCode::Enter => self.set_screen(Screen::MainMenu),
+                _ => self.set_screen(Screen::Title),
+            };
+        }
 
-            if let Some(v) = key_event {
-                if let KeyCode::Esc = v.code {
-                    game_condition = GameCondition::Ended;
+        let elapsed = self.screen_started.elapsed();
 
-                    break;
+        let demo = match &mut self.demo {
+            Some(demo) => demo,
+            None => {
+                if elapsed >= ATTRACT_PAGE_DURATION {
+                    self.set_attract_phase(self.attract_phase.next());
                 }
+                return;
             }
-            std::thread::sleep(std::time::Duration::from_millis(10));
+        };
+
+        if let Some(command) = self.bot.decide(demo) {
+            self.key_handler.apply_command(demo, command);
         }
 
-        Ok(game_condition)
+        let game_condition = demo.tick();
+        if game_condition != GameCondition::Running || elapsed >= DEMO_DURATION {
+            self.set_attract_phase(self.attract_phase.next());
+        }
     }
 
-    pub fn main_loop(&mut self) -> Result<()> {
-        let mut end_game = false;
-        self.tr.clear_screen()?;
+    fn high_score_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .high_scores
+            .get_entries()
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                format!(
+                    "{:>2}. {}  {:>7}  W{:<3} {} {}",
+                    i + 1,
+                    e.initials,
+                    e.score,
+                    e.wave,
+                    locale::pad_to_width(&e.mode.get_name(), 10),
+                    e.date
+                )
+            })
+            .collect();
 
-        let mut last_game = GameCondition::Ended;
+        if lines.is_empty() {
+            lines.push(locale::text("high_scores.empty"));
+        }
 
-        loop {
-            self.tr.disable_cursor();
-            self.tr.enable_raw_mode();
+        lines
+    }
 
-            last_game = self.game_loop()?;
+    fn render_attract(&mut self) {
+        let press_enter = locale::text("attract.press_enter");
 
-            self.tr.disable_raw_mode();
-            self.tr.enable_cursor();
+        match self.attract_phase {
+            AttractPhase::Demo => {
+                if let Some(demo) = &self.demo {
+                    self.gb.draw(demo);
+                    self.gb.draw_caption(&press_enter);
+                }
+                self.describe_screen(&locale::text("title.banner"), &[press_enter], None);
+            }
+            AttractPhase::HighScores => {
+                let mut lines = self.high_score_lines();
+                lines.push(String::new());
+                lines.push(press_enter);
 
-            if let GameCondition::Ended = last_game {
-                self.tr.draw_gameover1(&self.game, "Game Over");
+                self.show_window_screen(&locale::text("high_scores.title"), &lines, None);
             }
+            AttractPhase::Legend => {
+                let alien = SmallAlien::new(0, 0, &self.game.get_theme().alien);
+                let glyph = alien.get_symbol().to_string();
+                let points = alien.get_points();
 
-            if let GameCondition::Win = last_game {
-                self.tr.draw_gameover1(&self.game, "You Win!!!");
+                let lines = [
+                    locale::text_with(
+                        "legend.points",
+                        &[("glyph", glyph), ("points", points.to_string())],
+                    ),
+                    String::new(),
+                    press_enter,
+                ];
+                self.show_window_screen(&locale::text("legend.title"), &lines, None);
             }
+        }
+    }
+
+    fn start_game(&mut self) {
+        self.game.reset_game();
+        self.game
+            .set_enemy_rows_cols(self.config.formation_rows, self.config.formation_cols);
+        self.set_screen(Screen::WaveIntro);
+    }
 
-            if let GameCondition::Loss = last_game {
-                self.tr.draw_gameover1(&self.game, "You Loose :(");
+    fn end_game(&mut self, game_condition: GameCondition) {
+        self.game_condition = game_condition;
+        self.set_screen(Screen::GameOver);
+
+        let event = match game_condition {
+            GameCondition::Win => SoundEvent::WaveClear,
+            GameCondition::Loss => SoundEvent::GameOver,
+            GameCondition::Ended | GameCondition::Running => return,
+        };
+        self.play_sound(event);
+    }
+
+    fn play_sound(&mut self, event: SoundEvent) {
+        if let Err(e) = audio::play_event(event) {
+            self.game.log_event(&error_event("event.sound_missing", &e));
+        }
+    }
+
+    // every menu screen answers the cursor keys, enter and escape
+    fn menu_sound(&mut self, code: KeyCode) {
+        let event = match code {
+            KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right => SoundEvent::MenuMove,
+            KeyCode::Enter => SoundEvent::MenuSelect,
+            KeyCode::Esc | KeyCode::Backspace => SoundEvent::MenuBack,
+            _ => return,
+        };
+        self.play_sound(event);
+    }
+
+    // the game is over for good, qualifying scores go to the initials screen
+    fn finish_game(&mut self) {
+        if self.high_scores.qualifies(self.game.get_score()) {
+            self.initials = InitialsEntry::new();
+            self.set_screen(Screen::EnterInitials);
+        } else {
+            self.set_screen(Screen::MainMenu);
+        }
+    }
+
+    fn save_high_score(&mut self) {
+        let entry = HighScoreEntry {
+            initials: self.initials.get_initials(),
+            score: self.game.get_score(),
+            wave: self.game.get_wave(),
+            date: Timestamp::now().date(),
+            mode: self.game.get_mode(),
+        };
+        self.high_scores.insert(entry);
+
+        if let Err(e) = self.high_scores.save() {
+            self.game
+                .log_event(&error_event("event.high_scores_not_saved", &e));
+        }
+
+        self.game
+            .set_high_score(self.high_scores.get_best().unwrap_or(0.0));
+        self.set_screen(Screen::HighScores);
+    }
+
+    fn update_initials(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Up => self.initials.next_letter(),
+            KeyCode::Down => self.initials.previous_letter(),
+            KeyCode::Left | KeyCode::Backspace => self.initials.move_left(),
+            KeyCode::Right => self.initials.move_right(),
+            KeyCode::Char(ch) => self.initials.type_letter(ch),
+            KeyCode::Enter => self.save_high_score(),
+            KeyCode::Esc => self.set_screen(Screen::MainMenu),
+            _ => (),
+        }
+    }
+
+    // keys that work the same on every screen
+    fn handle_global_key(&mut self, key: &KeyEvent) {
+        if self.settings.is_rebinding() || self.screen == Screen::EnterInitials {
+            return;
+        }
+
+        match self.key_handler.get_command(key) {
+            Some(Command::Screenshot) => self.take_screenshot(),
+            Some(Command::Record) => self.toggle_recording(),
+            Some(Command::Mute) => self.toggle_mute(),
+            _ => (),
+        }
+    }
+
+    // saved right away, the next start is muted too
+    fn toggle_mute(&mut self) {
+        self.config.muted = !self.config.muted;
+        audio::set_muted(self.config.muted);
+
+        let event = if self.config.muted {
+            "event.muted"
+        } else {
+            "event.unmuted"
+        };
+        self.game.log_event(&locale::text(event));
+
+        if let Err(e) = self.config.save() {
+            self.game
+                .log_event(&error_event("event.settings_not_saved", &e));
+        }
+    }
+
+    fn leave_settings(&mut self) {
+        self.config = self.settings.get_config().clone();
+        self.apply_config();
+
+        if let Err(e) = self.config.save() {
+            self.game
+                .log_event(&error_event("event.settings_not_saved", &e));
+        }
+
+        self.set_screen(Screen::MainMenu);
+    }
+
+    fn update_main_menu(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Up => self.main_menu.select_previous(),
+            KeyCode::Down => self.main_menu.select_next(),
+            KeyCode::Esc => self.set_screen(Screen::Title),
+            KeyCode::Enter => match self.main_menu.get_selected_item() {
+                "menu.play" => self.start_game(),
+                "menu.mode" => self.set_screen(Screen::ModeSelect),
+                "menu.high_scores" => self.set_screen(Screen::HighScores),
+                "menu.settings" => {
+                    self.settings.set_config(&self.config);
+                    self.set_screen(Screen::Settings);
+                }
+                _ => self.set_screen(Screen::Quit),
+            },
+            _ => (),
+        }
+    }
+
+    fn update_mode_select(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Up => self.mode_menu.select_previous(),
+            KeyCode::Down => self.mode_menu.select_next(),
+            KeyCode::Esc => self.set_screen(Screen::MainMenu),
+            KeyCode::Enter => {
+                let mode = GameMode::all()[self.mode_menu.get_selected()];
+                self.game.set_mode(mode);
+                self.set_screen(Screen::MainMenu);
+            }
+            _ => (),
+        }
+    }
+
+    fn update_pause_menu(&mut self, key: &KeyEvent) {
+        if let Some(Command::Pause) = self.key_handler.get_command(key) {
+            return self.set_screen(Screen::Playing);
+        }
+
+        match key.code {
+            KeyCode::Up => self.pause_menu.select_previous(),
+            KeyCode::Down => self.pause_menu.select_next(),
+            KeyCode::Esc => self.set_screen(Screen::Playing),
+            KeyCode::Enter => match self.pause_menu.get_selected_item() {
+                "pause.resume" => self.set_screen(Screen::Playing),
+                "pause.restart" => self.start_game(),
+                _ => self.set_screen(Screen::MainMenu),
+            },
+            _ => (),
+        }
+    }
+
+    fn update_playing(&mut self, key: Option<KeyEvent>) {
+        if let Some(key) = key {
+            if let KeyCode::Esc = key.code {
+                return self.end_game(GameCondition::Ended);
+            }
+
+            if let Some(Command::Pause) = self.key_handler.get_command(&key) {
+                self.pause_menu.set_selected(0);
+                return self.set_screen(Screen::Paused);
             }
 
-            self.tr.disable_cursor();
-            self.tr.enable_raw_mode();
-            loop {
-                let key_event = self.key_handler.handle(&mut self.game);
-                if let Some(v) = key_event {
-                    if let KeyCode::Esc = v.code {
-                        end_game = true;
-                        break;
-                    } else if let KeyCode::Enter = v.code {
-                        break;
+            self.key_handler.apply(&mut self.game, &key);
+        }
+
+        let game_condition = self.game.tick();
+        if let GameCondition::Win | GameCondition::Loss = game_condition {
+            self.end_game(game_condition);
+        }
+    }
+
+    fn update(&mut self, key: Option<KeyEvent>) {
+        let code = key.map(|k| k.code);
+
+        match (self.screen, code) {
+            (Screen::Attract | Screen::WaveIntro | Screen::Playing | Screen::Quit, _) => (),
+            (_, Some(code)) => self.menu_sound(code),
+            (_, None) => (),
+        }
+
+        match self.screen {
+            Screen::Title => match code {
+                Some(KeyCode::Enter) => self.set_screen(Screen::MainMenu),
+                Some(KeyCode::Esc) => self.set_screen(Screen::Quit),
+                Some(_) => self.screen_started = std::time::Instant::now(),
+                None => {
+                    if self.screen_started.elapsed() >= ATTRACT_DELAY {
+                        self.set_screen(Screen::Attract);
+                        self.set_attract_phase(AttractPhase::Demo);
+                    }
+                }
+            },
+            Screen::Attract => self.update_attract(code),
+            Screen::MainMenu => {
+                if let Some(code) = code {
+                    self.update_main_menu(code);
+                }
+            }
+            Screen::ModeSelect => {
+                if let Some(code) = code {
+                    self.update_mode_select(code);
+                }
+            }
+            Screen::HighScores => {
+                if let Some(KeyCode::Esc | KeyCode::Enter) = code {
+                    self.set_screen(Screen::MainMenu);
+                }
+            }
+            Screen::Settings => {
+                if let Some(code) = code {
+                    if self.settings.handle(code) {
+                        self.leave_settings();
                     }
                 }
             }
+            Screen::WaveIntro => {
+                let skipped = matches!(code, Some(KeyCode::Enter));
+                if skipped || self.screen_started.elapsed() >= WAVE_INTRO_DURATION {
+                    self.set_screen(Screen::Playing);
+                }
+            }
+            Screen::Playing => self.update_playing(key),
+            Screen::Paused => {
+                if let Some(key) = &key {
+                    self.update_pause_menu(key);
+                }
+            }
+            Screen::GameOver => match code {
+                Some(KeyCode::Enter) if self.game_condition == GameCondition::Win => {
+                    self.game.next_wave();
+                    self.set_screen(Screen::WaveIntro);
+                }
+                Some(KeyCode::Enter | KeyCode::Esc) => self.finish_game(),
+                _ => (),
+            },
+            Screen::EnterInitials => {
+                if let Some(code) = code {
+                    self.update_initials(code);
+                }
+            }
+            Screen::Quit => (),
+        }
+    }
+
+    // the march speeds up as aliens die and pauses with the game, a lost
+    // game crossfades to the game over theme
+    fn update_music(&mut self) {
+        let result = match self.screen {
+            Screen::Playing => {
+                let boss = self.game.get_enemies_left() <= BOSS_ALIENS
+                    && self.game.get_enemies().len() > BOSS_ALIENS;
+                let track = if boss { Track::Boss } else { Track::March };
+
+                let base_step = self.game.get_enemy_move_duration();
+                let step = music::get_march_step(
+                    base_step,
+                    self.game.get_enemies_left(),
+                    self.game.get_enemies().len(),
+                );
+
+                self.music
+                    .play(track)
+                    .and_then(|_| self.music.set_tempo(step, base_step))
+            }
+            Screen::Paused => self.music.set_paused(true),
+            Screen::GameOver if self.game_condition == GameCondition::Loss => {
+                self.music.play(Track::GameOver)
+            }
+            // the game over theme plays out while the initials are typed
+            Screen::EnterInitials => Ok(()),
+            _ => self.music.stop(),
+        };
+
+        if let Err(e) = result {
+            self.game.log_event(&error_event("event.music_failed", &e));
+        }
+    }
+
+    fn render(&mut self) -> Result<()> {
+        match self.screen {
+            Screen::Title => {
+                let lines = [locale::text("title.start"), locale::text("title.quit")];
+                self.show_window_screen(&locale::text("title.banner"), &lines, None);
+            }
+            Screen::MainMenu => {
+                let mut items = translate_items(&self.main_menu);
+                items[1] =
+                    locale::text_with("menu.mode", &[("mode", self.game.get_mode().get_name())]);
 
-            if end_game {
-                break;
+                let selected = Some(self.main_menu.get_selected());
+                self.show_window_screen(&locale::text("title.banner"), &items, selected);
             }
-            let max_lives = self.game.get_max_lives();
-            let last_score = self.game.get_score();
-            let last_lives = self.game.get_lives();
+            Screen::ModeSelect => {
+                let items = translate_items(&self.mode_menu);
+                let selected = Some(self.mode_menu.get_selected());
+                self.show_window_screen(&locale::text("mode.title"), &items, selected);
+            }
+            Screen::HighScores => {
+                let mut lines = self.high_score_lines();
+                lines.push(String::new());
+                lines.push(locale::text("high_scores.back"));
+
+                self.show_window_screen(&locale::text("high_scores.title"), &lines, None);
+            }
+            Screen::EnterInitials => {
+                let cursor = self.initials.get_cursor();
+                let slots = self
+                    .initials
+                    .get_initials()
+                    .chars()
+                    .enumerate()
+                    .map(|(i, ch)| {
+                        if i == cursor {
+                            format!("[{}]", ch)
+                        } else {
+                            format!(" {} ", ch)
+                        }
+                    })
+                    .collect::<String>();
+
+                let lines = [
+                    locale::text_with(
+                        "initials.score",
+                        &[("score", self.game.get_score().to_string())],
+                    ),
+                    String::new(),
+                    slots,
+                    String::new(),
+                    locale::text("initials.hint"),
+                ];
+                self.show_window_screen(&locale::text("initials.title"), &lines, None);
+            }
+            Screen::Settings => {
+                let menu = self.settings.get_menu();
+                let items = menu.get_items().clone();
+                let selected = Some(menu.get_selected());
+                self.show_window_screen(&locale::text("settings.title"), &items, selected);
+            }
+            Screen::WaveIntro => {
+                self.gb.draw(&self.game);
 
-            self.game.reset_game();
-            if let GameCondition::Win = last_game {
-                self.game.set_lives(last_lives + 1);
-                self.game.set_score(last_score);
+                let title =
+                    locale::text_with("wave.title", &[("wave", self.game.get_wave().to_string())]);
+                let lines = [locale::text("wave.ready"), locale::text("wave.start")];
+                self.show_banner_screen(&title, &lines, None);
+            }
+            Screen::Playing => self.gb.draw(&self.game),
+            Screen::Paused => {
+                self.gb.draw(&self.game);
 
-                let (rows, cols) = self.game.get_enemies_rows_cols();
-                let rows = rows + 1;
-                let cols = cols + 1;
+                let items = translate_items(&self.pause_menu);
+                let selected = Some(self.pause_menu.get_selected());
+                self.show_banner_screen(&locale::text("pause.title"), &items, selected);
+            }
+            Screen::GameOver => {
+                self.gb.draw(&self.game);
 
-                self.game.set_enemy_rows_cols(rows, cols);
+                let (title, hint) = match self.game_condition {
+                    GameCondition::Win => ("game_over.win", "game_over.win_hint"),
+                    GameCondition::Loss => ("game_over.loss", "game_over.hint"),
+                    GameCondition::Ended | GameCondition::Running => {
+                        ("game_over.ended", "game_over.hint")
+                    }
+                };
+                self.show_banner_screen(&locale::text(title), &[locale::text(hint)], None);
             }
-            std::thread::sleep(std::time::Duration::from_millis(10));
+            Screen::Attract => self.render_attract(),
+            Screen::Quit => return Ok(()),
+        }
+
+        self.tr.draw(&self.gb)?;
+
+        if let Err(e) = self.recorder.capture(&self.gb) {
+            self.game
+                .log_event(&error_event("event.recording_failed", &e));
         }
+        self.poll_gif_jobs();
+        self.flush_narration();
+
+        Ok(())
+    }
+
+    pub fn main_loop(&mut self) -> Result<()> {
+        self.tr.clear_screen()?;
+        self.tr.disable_cursor();
+        self.tr.enable_raw_mode();
+
+        while self.screen != Screen::Quit {
+            let key = self.key_handler.read();
+
+            if let Some((width, height)) = self.key_handler.take_resize() {
+                self.resize(width, height)?;
+            }
+
+            if self.too_small {
+                if let Some(KeyCode::Esc) = key.map(|k| k.code) {
+                    self.set_screen(Screen::Quit);
+                }
+
+                std::thread::sleep(std::time::Duration::from_millis(10));
+                continue;
+            }
+
+            if let Some(key) = &key {
+                self.handle_global_key(key);
+            }
+
+            self.update(key);
+            self.update_music();
+            self.render()?;
+
+            std::thread::sleep(self.config.get_frame_duration());
+        }
+
+        self.finish_recordings();
 
         self.tr.disable_raw_mode();
         self.tr.enable_cursor();
+        self.tr.clear_screen()?;
 
         Ok(())
     }
 }
 
+// menus hold message keys, the labels follow the current language
+fn translate_items(menu: &Menu) -> Vec<String> {
+    menu.get_items()
+        .iter()
+        .map(|item| locale::text(item))
+        .collect()
+}
+
+fn error_event(key: &str, error: &anyhow::Error) -> String {
+    locale::text_with(key, &[("error", error.to_string())])
+}
+
+fn path_event(key: &str, path: &Path) -> String {
+    locale::text_with(key, &[("path", path.display().to_string())])
+}
+
+fn render_replay(path: &str) -> Result<()> {
+    let path = Path::new(path);
+    let gif_path = path.with_extension("gif");
+
+    Replay::load(path)?.render_gif(&gif_path)?;
+    println!("Rendered {}", gif_path.display());
+
+    Ok(())
+}
+
 fn main() -> Result<()> {
-    let mut game_manager = GameManager::new(128, 32);
-    let game = game_manager.get_game();
+    let options = Options::from_args()?;
+
+    if let Some(path) = &options.render_replay {
+        return render_replay(path);
+    }
+
+    if options.no_audio {
+        audio::set_backend(Box::new(NullBackend));
+    } else {
+        audio::start();
+    }
 
-    game.set_enemy_rows_cols(5, 2);
+    let mut game_manager = GameManager::new(&options)?;
     game_manager.main_loop()?;
 
     Ok(())
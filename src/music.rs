@@ -0,0 +1,243 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use anyhow::Result;
+use rodio::Source;
+
+use crate::audio::{self, MusicSource};
+use crate::synth::SAMPLE_RATE;
+
+const CROSSFADE: std::time::Duration = std::time::Duration::from_millis(800);
+const GAME_OVER_STEP: std::time::Duration = std::time::Duration::from_millis(450);
+
+// the four descending bass notes of the classic invader march, in Hz
+const MARCH_NOTES: [f32; 4] = [110.0, 98.0, 87.31, 82.41];
+const BOSS_NOTES: [f32; 4] = [110.0, 116.54, 110.0, 103.83];
+const GAME_OVER_NOTES: [f32; 4] = [196.0, 174.61, 155.56, 130.81];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Track {
+    March,
+    // the last few aliens of a wave
+    Boss,
+    GameOver,
+}
+
+// square wave notes, one per step; the step length is read while playing,
+// so the tempo can change without changing the pitch
+pub struct Melody {
+    notes: Vec<f32>,
+    // microseconds per note
+    step: Arc<AtomicU64>,
+    looped: bool,
+
+    note: usize,
+    position: u64,
+}
+
+impl Melody {
+    pub fn new(notes: &[f32], step: Arc<AtomicU64>, looped: bool) -> Self {
+        Self {
+            notes: notes.to_vec(),
+            step,
+            looped,
+            note: 0,
+            position: 0,
+        }
+    }
+}
+
+impl Iterator for Melody {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let step = self.step.load(Ordering::Relaxed) * SAMPLE_RATE as u64 / 1_000_000;
+        let step = step.max(1);
+
+        if self.position >= step {
+            self.position = 0;
+            self.note += 1;
+
+            if self.note >= self.notes.len() {
+                if !self.looped {
+                    return None;
+                }
+                self.note = 0;
+            }
+        }
+
+        let t = self.position as f32 / SAMPLE_RATE as f32;
+        self.position += 1;
+
+        // a short plucked note, the rest of the step is silent
+        let length = (step as f32 / SAMPLE_RATE as f32 * 0.6).min(0.25);
+        if t >= length {
+            return Some(0.0);
+        }
+
+        let frequency = self.notes[self.note];
+        let phase = (t * frequency).fract();
+        let square = if phase < 0.5 { 1.0 } else { -1.0 };
+        let envelope = 1.0 - t / length;
+
+        Some(square * envelope * 0.3)
+    }
+}
+
+impl Source for Melody {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<std::time::Duration> {
+        None
+    }
+}
+
+// picks the background track for the game state and keeps its tempo in
+// step with the formation
+pub struct MusicPlayer {
+    track: Option<Track>,
+    paused: bool,
+
+    // shared with the playing melody
+    step: Arc<AtomicU64>,
+    // the march interval the file is played at normal speed for
+    base_step: std::time::Duration,
+    // user-supplied march instead of the built-in one
+    file: Option<String>,
+    playing_file: bool,
+}
+
+impl Default for MusicPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// the march speeds up as aliens die, the last one at a quarter of
+// `base_step`; only the music does, the formation keeps its own pace
+pub fn get_march_step(
+    base_step: std::time::Duration,
+    left: usize,
+    total: usize,
+) -> std::time::Duration {
+    let total = total.max(1) as f32;
+
+    base_step.mul_f32(0.25 + 0.75 * left as f32 / total)
+}
+
+impl MusicPlayer {
+    pub fn new() -> Self {
+        Self {
+            track: None,
+            paused: false,
+            step: Arc::new(AtomicU64::new(500_000)),
+            base_step: std::time::Duration::from_millis(200),
+            file: None,
+            playing_file: false,
+        }
+    }
+
+    pub fn get_file(&self) -> Option<&String> {
+        self.file.as_ref()
+    }
+
+    // takes effect the next time the march starts
+    pub fn set_file(&mut self, file: Option<String>) {
+        self.file = file;
+    }
+
+    pub fn get_track(&self) -> Option<Track> {
+        self.track
+    }
+
+    // crossfades to `track` unless it is playing already; a march file that
+    // fails to load falls back to the built-in march and returns the error
+    pub fn play(&mut self, track: Track) -> Result<()> {
+        self.set_paused(false)?;
+
+        if self.track == Some(track) {
+            return Ok(());
+        }
+
+        self.track = Some(track);
+        self.playing_file = false;
+
+        let mut result = Ok(());
+        let source: MusicSource = match (track, &self.file) {
+            (Track::March, Some(file)) => match audio::load_music(file) {
+                Ok(source) => {
+                    // makes the next `set_tempo` send the file's speed
+                    self.playing_file = true;
+                    self.base_step = std::time::Duration::ZERO;
+                    source
+                }
+                Err(e) => {
+                    self.file = None;
+                    result = Err(e);
+                    Box::new(Melody::new(&MARCH_NOTES, self.step.clone(), true))
+                }
+            },
+            (Track::March, None) => Box::new(Melody::new(&MARCH_NOTES, self.step.clone(), true)),
+            (Track::Boss, _) => Box::new(Melody::new(&BOSS_NOTES, self.step.clone(), true)),
+            (Track::GameOver, _) => {
+                let step = Arc::new(AtomicU64::new(GAME_OVER_STEP.as_micros() as u64));
+                Box::new(Melody::new(&GAME_OVER_NOTES, step, false))
+            }
+        };
+
+        audio::play_music(source, CROSSFADE)?;
+        result
+    }
+
+    pub fn stop(&mut self) -> Result<()> {
+        if self.track.is_none() {
+            return Ok(());
+        }
+
+        self.track = None;
+        self.playing_file = false;
+        audio::stop_music(CROSSFADE)
+    }
+
+    pub fn set_paused(&mut self, paused: bool) -> Result<()> {
+        if self.paused == paused {
+            return Ok(());
+        }
+
+        self.paused = paused;
+        audio::pause_music(paused)
+    }
+
+    // `step` is the music's current beat, see `get_march_step`, and
+    // `base_step` the one it started the wave with; a march file speeds up
+    // by their ratio
+    pub fn set_tempo(
+        &mut self,
+        step: std::time::Duration,
+        base_step: std::time::Duration,
+    ) -> Result<()> {
+        let micros = step.as_micros() as u64;
+        if self.step.swap(micros, Ordering::Relaxed) == micros && self.base_step == base_step {
+            return Ok(());
+        }
+
+        self.base_step = base_step;
+
+        if self.playing_file {
+            let speed = base_step.as_secs_f32() / step.as_secs_f32().max(0.001);
+            audio::set_music_speed(speed)?;
+        }
+
+        Ok(())
+    }
+}
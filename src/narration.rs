@@ -0,0 +1,34 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use anyhow::{anyhow as error, Result};
+
+// one plain line per event, written somewhere a screen reader can follow
+// while the terminal itself is busy with the playfield: a file or a named
+// pipe, never the terminal, which would print over the playfield
+pub struct Narrator {
+    output: Box<dyn Write + Send>,
+}
+
+impl Narrator {
+    pub fn new(target: &str) -> Result<Self> {
+        if target == "-" {
+            return Err(error!(
+                "Narration can't go to the game's terminal, use a file or named pipe"
+            ));
+        }
+
+        let output = OpenOptions::new().create(true).append(true).open(target)?;
+
+        Ok(Self {
+            output: Box::new(output),
+        })
+    }
+
+    pub fn say(&mut self, line: &str) -> Result<()> {
+        writeln!(self.output, "{}", line)?;
+        self.output.flush()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use crossterm::style::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::locale;
+
+// what a buffer cell shows, the palette decides the color for it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CellRole {
+    Text,
+    Highlight,
+    Border,
+    Banner,
+    Ship,
+    Alien,
+    Bullet,
+    EnemyBullet,
+    Particle,
+    // the starfield behind the playfield
+    Background,
+}
+
+impl CellRole {
+    pub fn all() -> Vec<CellRole> {
+        vec![
+            CellRole::Text,
+            CellRole::Highlight,
+            CellRole::Border,
+            CellRole::Banner,
+            CellRole::Ship,
+            CellRole::Alien,
+            CellRole::Bullet,
+            CellRole::EnemyBullet,
+            CellRole::Particle,
+            CellRole::Background,
+        ]
+    }
+
+    // one letter per role, how replay files store them
+    pub fn get_code(&self) -> char {
+        match self {
+            CellRole::Text => 't',
+            CellRole::Highlight => 'h',
+            CellRole::Border => 'o',
+            CellRole::Banner => 'n',
+            CellRole::Ship => 's',
+            CellRole::Alien => 'a',
+            CellRole::Bullet => 'b',
+            CellRole::EnemyBullet => 'e',
+            CellRole::Particle => 'p',
+            CellRole::Background => 'g',
+        }
+    }
+
+    pub fn from_code(code: char) -> Option<CellRole> {
+        Self::all().into_iter().find(|r| r.get_code() == code)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Palette {
+    // the terminal's own colors
+    Standard,
+    // bright colors on a black background
+    HighContrast,
+    // Okabe-Ito colors, told apart with every common type of color blindness
+    Colorblind,
+}
+
+impl Palette {
+    pub fn all() -> Vec<Palette> {
+        vec![
+            Palette::Standard,
+            Palette::HighContrast,
+            Palette::Colorblind,
+        ]
+    }
+
+    pub fn get_name(&self) -> String {
+        match self {
+            Palette::Standard => locale::text("palette.standard"),
+            Palette::HighContrast => locale::text("palette.high_contrast"),
+            Palette::Colorblind => locale::text("palette.colorblind"),
+        }
+    }
+
+    pub fn background(&self) -> Color {
+        match self {
+            Palette::Standard | Palette::Colorblind => Color::Reset,
+            Palette::HighContrast => Color::Black,
+        }
+    }
+
+    pub fn foreground(&self, role: CellRole) -> Color {
+        match self {
+            // dimmed stars keep the background apart from the gameplay glyphs
+            Palette::Standard => match role {
+                CellRole::Background => Color::DarkGrey,
+                _ => Color::Reset,
+            },
+            Palette::HighContrast => match role {
+                CellRole::Text | CellRole::Border | CellRole::Banner => Color::White,
+                CellRole::Highlight | CellRole::Alien => Color::Yellow,
+                CellRole::Ship | CellRole::Bullet => Color::Cyan,
+                CellRole::EnemyBullet => Color::Magenta,
+                CellRole::Particle => Color::Grey,
+                CellRole::Background => Color::DarkGrey,
+            },
+            Palette::Colorblind => match role {
+                CellRole::Text | CellRole::Border => Color::Reset,
+                CellRole::Highlight | CellRole::Particle => Color::Rgb {
+                    r: 240,
+                    g: 228,
+                    b: 66,
+                },
+                CellRole::Banner | CellRole::Ship => Color::Rgb {
+                    r: 0,
+                    g: 114,
+                    b: 178,
+                },
+                CellRole::Alien => Color::Rgb {
+                    r: 230,
+                    g: 159,
+                    b: 0,
+                },
+                CellRole::Bullet => Color::Rgb {
+                    r: 86,
+                    g: 180,
+                    b: 233,
+                },
+                CellRole::EnemyBullet => Color::Rgb {
+                    r: 213,
+                    g: 94,
+                    b: 0,
+                },
+                CellRole::Background => Color::DarkGrey,
+            },
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use rand::Rng;
+
+use crate::container::{Container, Point};
+use crate::theme::Theme;
+
+// purely visual, particles never take part in collision detection
+#[derive(Debug, Clone)]
+pub struct Particle {
+    x: f32,
+    y: f32,
+
+    // cells per second
+    velocity_x: f32,
+    velocity_y: f32,
+
+    // fade-out sequence, spread evenly over the lifetime
+    frames: Vec<String>,
+    lifetime: std::time::Duration,
+    spawned_at: std::time::Instant,
+    last_tick: std::time::Instant,
+}
+
+impl Particle {
+    pub fn new(
+        origin: &Point,
+        velocity_x: f32,
+        velocity_y: f32,
+        frames: &[&str],
+        lifetime: std::time::Duration,
+    ) -> Self {
+        let now = std::time::Instant::now();
+
+        Self {
+            x: origin.x as f32,
+            y: origin.y as f32,
+            velocity_x,
+            velocity_y,
+            frames: frames.iter().map(|f| f.to_string()).collect(),
+            lifetime,
+            spawned_at: now,
+            last_tick: now,
+        }
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        let age = self.spawned_at.elapsed().as_secs_f32();
+        let progress = age / self.lifetime.as_secs_f32();
+
+        let index = (progress * self.frames.len() as f32) as usize;
+        let index = index.min(self.frames.len() - 1);
+
+        &self.frames[index]
+    }
+
+    pub fn get_pos(&self) -> Option<Point> {
+        let x = self.x.round();
+        let y = self.y.round();
+
+        if x < 0.0 || y < 0.0 {
+            return None;
+        }
+
+        Some(Point {
+            x: x as usize,
+            y: y as usize,
+        })
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.spawned_at.elapsed() >= self.lifetime
+    }
+
+    pub fn shift_timers(&mut self, by: std::time::Duration) {
+        self.spawned_at += by;
+        self.last_tick += by;
+    }
+
+    pub fn tick(&mut self) {
+        let now = std::time::Instant::now();
+        let dt = (now - self.last_tick).as_secs_f32();
+
+        self.x += self.velocity_x * dt;
+        self.y += self.velocity_y * dt;
+        self.last_tick = now;
+    }
+}
+
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+    max_particles: usize,
+    // reduced motion turns spawning off
+    enabled: bool,
+
+    explosion_frames: Vec<String>,
+    debris_frames: Vec<String>,
+    spark_frames: Vec<String>,
+}
+
+impl ParticleSystem {
+    pub fn new(max_particles: usize) -> Self {
+        let mut system = Self {
+            particles: vec![],
+            max_particles,
+            enabled: true,
+            explosion_frames: vec![],
+            debris_frames: vec![],
+            spark_frames: vec![],
+        };
+
+        system.set_theme(&Theme::default());
+        system
+    }
+
+    // only affects particles spawned from now on
+    pub fn set_theme(&mut self, theme: &Theme) {
+        self.explosion_frames = theme.explosion.clone();
+        self.debris_frames = theme.debris.clone();
+        self.spark_frames = theme.spark.clone();
+    }
+
+    pub fn get_particles(&self) -> &Vec<Particle> {
+        &self.particles
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+
+        if !enabled {
+            self.clear();
+        }
+    }
+
+    pub fn spawn(&mut self, particle: Particle) {
+        if !self.enabled {
+            return;
+        }
+
+        if self.particles.len() >= self.max_particles {
+            self.particles.remove(0);
+        }
+
+        self.particles.push(particle);
+    }
+
+    // scatter `count` particles in random directions from `origin`
+    fn burst(
+        &mut self,
+        origin: &Point,
+        count: usize,
+        speed: f32,
+        frames: &[String],
+        lifetime: std::time::Duration,
+    ) {
+        let mut rng = rand::thread_rng();
+        let frames: Vec<&str> = frames.iter().map(|f| f.as_str()).collect();
+
+        for _ in 0..count {
+            let angle: f32 = rng.gen_range(0.0..std::f32::consts::TAU);
+            let speed = rng.gen_range(speed / 2.0..=speed);
+
+            // terminal cells are roughly twice as tall as they are wide
+            let velocity_x = angle.cos() * speed;
+            let velocity_y = angle.sin() * speed / 2.0;
+
+            let particle = Particle::new(origin, velocity_x, velocity_y, &frames, lifetime);
+            self.spawn(particle);
+        }
+    }
+
+    pub fn spawn_explosion(&mut self, origin: &Point) {
+        self.burst(
+            origin,
+            6,
+            8.0,
+            &self.explosion_frames.clone(),
+            std::time::Duration::from_millis(400),
+        );
+    }
+
+    pub fn spawn_ship_destruction(&mut self, ship: &Container) {
+        for x in ship.top.x..=ship.bottom.x {
+            let origin = Point { x, y: ship.top.y };
+            self.spawn_debris(&origin, 3);
+        }
+
+        let middle = Point {
+            x: ship.top.x + ship.get_width() / 2,
+            y: ship.top.y,
+        };
+        self.spawn_explosion(&middle);
+    }
+
+    pub fn spawn_debris(&mut self, origin: &Point, count: usize) {
+        self.burst(
+            origin,
+            count,
+            5.0,
+            &self.debris_frames.clone(),
+            std::time::Duration::from_millis(700),
+        );
+    }
+
+    pub fn spawn_impact(&mut self, origin: &Point) {
+        self.burst(
+            origin,
+            3,
+            4.0,
+            &self.spark_frames.clone(),
+            std::time::Duration::from_millis(200),
+        );
+    }
+
+    // moves every particle and drops the ones that faded out or left `bounds`
+    pub fn tick(&mut self, bounds: &Container) {
+        for p in &mut self.particles {
+            p.tick();
+        }
+
+        self.particles.retain(|p| {
+            if p.is_expired() {
+                return false;
+            }
+
+            match p.get_pos() {
+                Some(pos) => {
+                    pos.x > bounds.top.x
+                        && pos.x < bounds.bottom.x
+                        && pos.y > bounds.top.y
+                        && pos.y < bounds.bottom.y
+                }
+                None => false,
+            }
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    pub fn shift_timers(&mut self, by: std::time::Duration) {
+        for p in &mut self.particles {
+            p.shift_timers(by);
+        }
+    }
+}
@@ -0,0 +1,353 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow as error, Result};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame};
+use unicode_width::UnicodeWidthChar;
+
+use crate::game_buffer::GameBuffer;
+use crate::palette::{CellRole, Palette};
+use crate::screenshot::rasterize_cells;
+use crate::timestamp::Timestamp;
+
+// version 2 added the palette and a role line after every row, version 1
+// files still load with every cell drawn as text
+const REPLAY_HEADER: &str = "invader-replay 2";
+const REPLAY_HEADER_V1: &str = "invader-replay 1";
+
+// GIF delays are in hundredths of a second, shorter ones get slowed down by
+// most viewers anyway
+const MIN_FRAME_DELAY_MS: u64 = 20;
+const LAST_FRAME_DELAY_MS: u64 = 100;
+
+// splits a saved row back into cells, a wide character is followed by the
+// empty cell it covered in the game buffer
+fn row_cells(row: &str) -> Vec<String> {
+    let mut cells = vec![];
+
+    for ch in row.chars() {
+        cells.push(ch.to_string());
+
+        if UnicodeWidthChar::width(ch) == Some(2) {
+            cells.push(String::new());
+        }
+    }
+
+    cells
+}
+
+// pads `grid` with `fill` or cuts it to `rows` by `cols`, a frame captured
+// after the terminal was resized has to fit the size the GIF started with
+fn fit<T: Clone>(grid: &[Vec<T>], rows: usize, cols: usize, fill: T) -> Vec<Vec<T>> {
+    (0..rows)
+        .map(|y| {
+            (0..cols)
+                .map(|x| {
+                    grid.get(y)
+                        .and_then(|row| row.get(x))
+                        .cloned()
+                        .unwrap_or_else(|| fill.clone())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayFrame {
+    // milliseconds since the recording started
+    pub at_ms: u64,
+    pub cells: Vec<Vec<String>>,
+    pub roles: Vec<Vec<CellRole>>,
+}
+
+// the text content of every captured frame, cheap to keep around while
+// recording and rendered to pixels only when the GIF gets encoded
+#[derive(Debug, Clone)]
+pub struct Replay {
+    frames: Vec<ReplayFrame>,
+    // the colors the frames are rendered in
+    palette: Palette,
+}
+
+impl Default for Replay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Replay {
+    pub fn new() -> Self {
+        Self {
+            frames: vec![],
+            palette: Palette::Standard,
+        }
+    }
+
+    pub fn push(&mut self, at_ms: u64, cells: &[Vec<String>], roles: &[Vec<CellRole>]) {
+        self.frames.push(ReplayFrame {
+            at_ms,
+            cells: cells.to_vec(),
+            roles: roles.to_vec(),
+        });
+    }
+
+    pub fn get_palette(&self) -> Palette {
+        self.palette
+    }
+
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+
+    pub fn get_frames(&self) -> &Vec<ReplayFrame> {
+        &self.frames
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut writer = ReplayWriter::create(path, self.palette)?;
+
+        for frame in &self.frames {
+            writer.write_frame(frame.at_ms, &frame.cells, &frame.roles)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut lines = reader.lines();
+
+        let header = lines.next().ok_or(error!("Replay file is empty"))??;
+        let has_roles = match header.as_str() {
+            REPLAY_HEADER => true,
+            REPLAY_HEADER_V1 => false,
+            _ => return Err(error!("Not a replay file: {}", path.display())),
+        };
+
+        let mut replay = Self::new();
+
+        if has_roles {
+            let line = lines.next().ok_or(error!("Replay file has no palette"))??;
+            let name = line
+                .strip_prefix("palette ")
+                .ok_or(error!("Malformed palette line: {}", line))?;
+            replay.palette = serde_json::from_str(name)?;
+        }
+
+        while let Some(line) = lines.next() {
+            let line = line?;
+            let mut parts = line.split_whitespace();
+
+            let (Some("frame"), Some(at_ms), Some(rows)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                return Err(error!("Malformed frame header: {}", line));
+            };
+            let at_ms: u64 = at_ms.parse()?;
+            let rows: usize = rows.parse()?;
+
+            let mut cells = vec![];
+            let mut roles = vec![];
+            for _ in 0..rows {
+                let row = lines.next().ok_or(error!("Replay frame is truncated"))??;
+                cells.push(row_cells(&row));
+
+                if has_roles {
+                    let line = lines.next().ok_or(error!("Replay frame is truncated"))??;
+                    let row_roles = line
+                        .chars()
+                        .map(|c| CellRole::from_code(c).ok_or(error!("Unknown cell role: {}", c)))
+                        .collect::<Result<Vec<CellRole>>>()?;
+                    roles.push(row_roles);
+                }
+            }
+
+            replay.frames.push(ReplayFrame {
+                at_ms,
+                cells,
+                roles,
+            });
+        }
+
+        Ok(replay)
+    }
+
+    pub fn render_gif(&self, path: &Path) -> Result<()> {
+        if self.is_empty() {
+            return Err(error!("Nothing to render, the replay has no frames"));
+        }
+
+        let mut encoder = GifEncoder::new_with_speed(BufWriter::new(File::create(path)?), 10);
+        encoder.set_repeat(Repeat::Infinite)?;
+
+        // every frame gets the first one's size
+        let first = &self.frames[0].cells;
+        let rows = first.len();
+        let cols = first.iter().map(|row| row.len()).max().unwrap_or(0);
+
+        for (i, frame) in self.frames.iter().enumerate() {
+            // each frame stays up until the next one was captured; the file
+            // may have been edited, so the timestamps can't be trusted
+            let delay_ms = match self.frames.get(i + 1) {
+                Some(next) => next.at_ms.saturating_sub(frame.at_ms),
+                None => LAST_FRAME_DELAY_MS,
+            };
+            let delay_ms = delay_ms.clamp(MIN_FRAME_DELAY_MS, u32::MAX as u64);
+
+            let cells = fit(&frame.cells, rows, cols, " ".to_string());
+            let roles = fit(&frame.roles, rows, cols, CellRole::Text);
+
+            let img = rasterize_cells(&cells, &roles, self.palette);
+            let delay = Delay::from_numer_denom_ms(delay_ms as u32, 1);
+            encoder.encode_frame(Frame::from_parts(img, 0, 0, delay))?;
+        }
+
+        Ok(())
+    }
+}
+
+// writes a replay file frame by frame: plain text, a header line and
+// "palette <name>", then per frame "frame <ms> <rows>" followed by every
+// row and the role letters of its cells on the line below
+pub struct ReplayWriter {
+    writer: BufWriter<File>,
+}
+
+impl ReplayWriter {
+    pub fn create(path: &Path, palette: Palette) -> Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{}", REPLAY_HEADER)?;
+        writeln!(writer, "palette {}", serde_json::to_string(&palette)?)?;
+        writer.flush()?;
+
+        Ok(Self { writer })
+    }
+
+    // every frame is flushed right away, a crash keeps what was captured
+    pub fn write_frame(
+        &mut self,
+        at_ms: u64,
+        cells: &[Vec<String>],
+        roles: &[Vec<CellRole>],
+    ) -> Result<()> {
+        writeln!(self.writer, "frame {} {}", at_ms, cells.len())?;
+        for (i, row) in cells.iter().enumerate() {
+            let row_roles: String = roles
+                .get(i)
+                .map(|r| r.iter().map(|role| role.get_code()).collect())
+                .unwrap_or_default();
+
+            writeln!(self.writer, "{}", row.concat())?;
+            writeln!(self.writer, "{}", row_roles)?;
+        }
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
+
+// frames go straight to the .replay file while recording, the GIF is
+// rendered from that file once the recording stops
+pub struct Recorder {
+    fps: u32,
+    dir: PathBuf,
+    palette: Palette,
+
+    // None while not recording
+    writer: Option<ReplayWriter>,
+    name: String,
+    started_at: std::time::Instant,
+    last_capture: Option<std::time::Instant>,
+}
+
+impl Recorder {
+    pub fn new(dir: &str, fps: u32) -> Self {
+        Self {
+            fps: fps.max(1),
+            dir: dir.into(),
+            palette: Palette::Standard,
+            writer: None,
+            name: String::new(),
+            started_at: std::time::Instant::now(),
+            last_capture: None,
+        }
+    }
+
+    pub fn get_fps(&self) -> u32 {
+        self.fps
+    }
+
+    pub fn set_fps(&mut self, fps: u32) {
+        self.fps = fps.max(1);
+    }
+
+    // the colors GIFs are rendered in, from the next recording on
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    pub fn start(&mut self) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        self.name = format!("invader-{}", Timestamp::now().file_stamp());
+
+        let path = self.dir.join(format!("{}.replay", self.name));
+        self.writer = Some(ReplayWriter::create(&path, self.palette)?);
+        self.started_at = std::time::Instant::now();
+        self.last_capture = None;
+
+        Ok(())
+    }
+
+    // keeps at most `fps` frames per second, extra calls are dropped; the
+    // recording stops when a frame can't be written
+    pub fn capture(&mut self, game_buffer: &GameBuffer) -> Result<()> {
+        let Some(writer) = self.writer.as_mut() else {
+            return Ok(());
+        };
+
+        let now = std::time::Instant::now();
+        let frame_duration = std::time::Duration::from_secs(1) / self.fps;
+
+        if let Some(last) = self.last_capture {
+            if now - last < frame_duration {
+                return Ok(());
+            }
+        }
+
+        let at_ms = (now - self.started_at).as_millis() as u64;
+        self.last_capture = Some(now);
+
+        let result = writer.write_frame(at_ms, game_buffer.get_buffer(), game_buffer.get_roles());
+        if result.is_err() {
+            self.writer = None;
+        }
+
+        result
+    }
+
+    // closes the replay and encodes the GIF from it on a background thread,
+    // since that takes a while for longer recordings
+    pub fn stop(&mut self) -> Result<std::thread::JoinHandle<Result<PathBuf>>> {
+        let writer = self.writer.take().ok_or(error!("Not recording"))?;
+        drop(writer);
+
+        let replay_path = self.dir.join(format!("{}.replay", self.name));
+        let gif_path = self.dir.join(format!("{}.gif", self.name));
+
+        Ok(std::thread::spawn(move || {
+            Replay::load(&replay_path)?.render_gif(&gif_path)?;
+            Ok(gif_path)
+        }))
+    }
+}
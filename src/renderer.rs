@@ -1,20 +1,17 @@
 use std::io::Write;
 
-use crate::{
-    container::{Container, Point},
-    game::Game,
-    text_processing::process_text,
-};
+use crate::{container::Container, game::Game};
 
 use anyhow::Result;
 
-use crossterm::{cursor, execute, ExecutableCommand};
+use crossterm::{cursor, execute, queue, style, ExecutableCommand};
 
 use crate::game_buffer::GameBuffer;
+use crate::palette::Palette;
 
 pub struct TerminalRenderer {
     window_container: Container,
-    prev_window_size: Container,
+    palette: Palette,
 
     raw_mode: bool,
     cursor: bool,
@@ -24,21 +21,9 @@ impl TerminalRenderer {
     pub fn new(game: &Game) -> TerminalRenderer {
         let window_container = game.get_window();
 
-        TerminalRenderer::set_size(&window_container).expect("Unable to resize terminal window");
-        let prev_size =
-            crossterm::terminal::window_size().expect("Unable to get terminal window size");
-
-        let prev_window_size = Container::new(
-            Point { x: 0, y: 0 },
-            Point {
-                x: prev_size.columns as usize,
-                y: prev_size.rows as usize,
-            },
-        );
-
         TerminalRenderer {
             window_container,
-            prev_window_size,
+            palette: Palette::Standard,
             raw_mode: false,
             cursor: true,
         }
@@ -59,16 +44,41 @@ impl TerminalRenderer {
         Ok(())
     }
 
+    pub fn get_palette(&self) -> Palette {
+        self.palette
+    }
+
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+
     pub fn draw(&self, game_buffer: &GameBuffer) -> Result<()> {
         let buff = game_buffer.get_buffer();
+        let roles = game_buffer.get_roles();
+        let mut stdout = std::io::stdout();
+
+        queue!(stdout, style::SetBackgroundColor(self.palette.background()))?;
+
+        // colors only change between cells of a different role
+        let mut current_role = None;
 
         for i in 0..game_buffer.get_rows() {
             for j in 0..game_buffer.get_cols() {
-                let item = &buff[i][j];
-                Self::goto_write(j, i, item);
+                let role = roles[i][j];
+                if current_role != Some(role) {
+                    let color = self.palette.foreground(role);
+                    queue!(stdout, style::SetForegroundColor(color))?;
+                    current_role = Some(role);
+                }
+
+                queue!(stdout, cursor::MoveTo(j as u16, i as u16))?;
+                stdout.write_all(buff[i][j].as_bytes())?;
             }
         }
 
+        queue!(stdout, style::ResetColor)?;
+        stdout.flush()?;
+
         Ok(())
     }
 
@@ -84,72 +94,15 @@ impl TerminalRenderer {
         self.window_container.clone()
     }
 
-    pub fn set_size(container: &Container) -> Result<()> {
-        let cols = container.get_width();
-        let rows = container.get_height();
-
-        execute!(
-            std::io::stdout(),
-            crossterm::terminal::SetSize(cols as u16, rows as u16)
-        )?;
-
-        Ok(())
+    pub fn set_window_container(&mut self, container: &Container) {
+        self.window_container = container.clone();
     }
 
-    pub fn draw_gameover(&self) -> Result<()> {
-        let window_container = self.get_window_container();
-
-        let buff = process_text(window_container.clone());
-
-        for row in 0..window_container.get_height() {
-            for j in 0..window_container.get_width() {
-                execute!(std::io::stdout(), cursor::MoveTo(j as u16, row as u16))?;
-                let item = &buff[row][j];
-
-                let mut pix = false;
-                let threshold = 10u8;
-                if item > &threshold {
-                    pix = true;
-                }
-
-                let mut symbol = "▮";
-                if !pix {
-                    symbol = " ";
-                }
+    // the real size of the terminal as (cols, rows)
+    pub fn terminal_size() -> Result<(usize, usize)> {
+        let (cols, rows) = crossterm::terminal::size()?;
 
-                std::io::stdout().write_all(symbol.as_bytes())?;
-            }
-        }
-
-        Ok(())
-    }
-    pub fn draw_gameover1(&self, game: &Game, text: &str) {
-        let playable_area = game.get_playablearea();
-        let mid_y = playable_area.get_height() / 2;
-        let mid_x = playable_area.get_width() / 2;
-
-        let mid_x_quater = mid_x / 2;
-
-        let mut text = text.to_string();
-        let spaces = (0..mid_x_quater / 3).map(|_| " ").collect::<String>();
-
-        text = format!("{}{}", spaces, text);
-
-        let output = cfonts::render(cfonts::Options {
-            text: text,
-            font: cfonts::Fonts::FontTiny,
-
-            ..cfonts::Options::default()
-        });
-
-        TerminalRenderer::goto_write(mid_x / 2, mid_y - 1, &output.text);
-        TerminalRenderer::goto_write(
-            mid_x / 6,
-            mid_y + 4,
-            "Press any [Enter] to continue or [ESC] to exit",
-        );
-
-        TerminalRenderer::goto_write(0, game.get_window().get_height(), "");
+        Ok((cols as usize, rows as usize))
     }
 
     pub fn enable_raw_mode(&mut self) {
@@ -186,8 +139,17 @@ impl TerminalRenderer {
     }
 }
 
-//impl Drop for TerminalRenderer {
-//    fn drop(&mut self) {
-//        TerminalRenderer::set_size(&self.prev_window_size).expect("Unable to resize window");
-//    }
-//}
+// hand the terminal back in the state we found it, even on early exit
+impl Drop for TerminalRenderer {
+    fn drop(&mut self) {
+        if self.raw_mode {
+            let _ = crossterm::terminal::disable_raw_mode();
+        }
+
+        if !self.cursor {
+            let _ = execute!(std::io::stdout(), crossterm::cursor::Show);
+        }
+
+        let _ = execute!(std::io::stdout(), style::ResetColor);
+    }
+}
@@ -0,0 +1,86 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Screen {
+    Title,
+    MainMenu,
+    ModeSelect,
+    HighScores,
+    Settings,
+    WaveIntro,
+    Playing,
+    Paused,
+    GameOver,
+    EnterInitials,
+    // the idle title screen cycles through these until a key is pressed
+    Attract,
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttractPhase {
+    Demo,
+    HighScores,
+    Legend,
+}
+
+impl AttractPhase {
+    pub fn next(&self) -> AttractPhase {
+        match self {
+            AttractPhase::Demo => AttractPhase::HighScores,
+            AttractPhase::HighScores => AttractPhase::Legend,
+            AttractPhase::Legend => AttractPhase::Demo,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Menu {
+    items: Vec<String>,
+    selected: usize,
+}
+
+impl Menu {
+    pub fn new(items: &[&str]) -> Self {
+        Self {
+            items: items.iter().map(|i| i.to_string()).collect(),
+            selected: 0,
+        }
+    }
+
+    pub fn get_items(&self) -> &Vec<String> {
+        &self.items
+    }
+
+    pub fn set_items(&mut self, items: &[String]) {
+        self.items = items.to_vec();
+        self.selected = self.selected.min(self.items.len().saturating_sub(1));
+    }
+
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn set_selected(&mut self, selected: usize) {
+        self.selected = selected.min(self.items.len().saturating_sub(1));
+    }
+
+    pub fn get_selected_item(&self) -> &str {
+        &self.items[self.selected]
+    }
+
+    // wraps around at both ends
+    pub fn select_next(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        self.selected = (self.selected + 1) % self.items.len();
+    }
+
+    pub fn select_previous(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        self.selected = (self.selected + self.items.len() - 1) % self.items.len();
+    }
+}
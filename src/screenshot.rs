@@ -0,0 +1,136 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use ab_glyph::{FontRef, PxScale};
+use anyhow::Result;
+use crossterm::style::Color;
+use image::{Rgba, RgbaImage};
+
+use crate::game_buffer::GameBuffer;
+use crate::palette::{CellRole, Palette};
+use crate::timestamp::Timestamp;
+
+pub const CELL_WIDTH: u32 = 10;
+pub const CELL_HEIGHT: u32 = 20;
+
+const BACKGROUND: Rgba<u8> = Rgba([0, 0, 0, 255]);
+const FOREGROUND: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+static FONT: OnceLock<FontRef<'static>> = OnceLock::new();
+
+// parsed on first use, every GIF frame is drawn with the same one
+fn font() -> &'static FontRef<'static> {
+    FONT.get_or_init(|| {
+        FontRef::try_from_slice(include_bytes!("../assets/fonts/DejaVuSans.ttf"))
+            .expect("Bundled font is invalid")
+    })
+}
+
+// the terminal's own colors are unknown, `Reset` becomes `default`; named
+// colors get the usual xterm values
+fn to_rgba(color: Color, default: Rgba<u8>) -> Rgba<u8> {
+    let rgb = |r, g, b| Rgba([r, g, b, 255]);
+
+    match color {
+        Color::Reset => default,
+        Color::Black => rgb(0, 0, 0),
+        Color::DarkGrey => rgb(128, 128, 128),
+        Color::Red => rgb(255, 0, 0),
+        Color::DarkRed => rgb(128, 0, 0),
+        Color::Green => rgb(0, 255, 0),
+        Color::DarkGreen => rgb(0, 128, 0),
+        Color::Yellow => rgb(255, 255, 0),
+        Color::DarkYellow => rgb(128, 128, 0),
+        Color::Blue => rgb(0, 0, 255),
+        Color::DarkBlue => rgb(0, 0, 128),
+        Color::Magenta => rgb(255, 0, 255),
+        Color::DarkMagenta => rgb(128, 0, 128),
+        Color::Cyan => rgb(0, 255, 255),
+        Color::DarkCyan => rgb(0, 128, 128),
+        Color::White => rgb(255, 255, 255),
+        Color::Grey => rgb(192, 192, 192),
+        Color::Rgb { r, g, b } => rgb(r, g, b),
+        Color::AnsiValue(_) => default,
+    }
+}
+
+pub fn rasterize(game_buffer: &GameBuffer, palette: Palette) -> RgbaImage {
+    rasterize_cells(game_buffer.get_buffer(), game_buffer.get_roles(), palette)
+}
+
+// draws every cell into a fixed size box, one glyph per cell, colored by
+// its role like the terminal does; cells without a role are text
+pub fn rasterize_cells(
+    cells: &[Vec<String>],
+    roles: &[Vec<CellRole>],
+    palette: Palette,
+) -> RgbaImage {
+    let font = font();
+
+    let scale = PxScale {
+        x: CELL_HEIGHT as f32 * 0.9,
+        y: CELL_HEIGHT as f32 * 0.9,
+    };
+
+    let cols = cells.iter().map(|row| row.len()).max().unwrap_or(0);
+    let width = cols as u32 * CELL_WIDTH;
+    let height = cells.len() as u32 * CELL_HEIGHT;
+    let background = to_rgba(palette.background(), BACKGROUND);
+    let mut img = RgbaImage::from_pixel(width, height, background);
+
+    for (row, row_cells) in cells.iter().enumerate() {
+        for (col, cell) in row_cells.iter().enumerate() {
+            if cell.trim().is_empty() {
+                continue;
+            }
+
+            let (glyph_width, _) = imageproc::drawing::text_size(scale, font, cell);
+            let offset_x = (CELL_WIDTH as i32 - glyph_width as i32) / 2;
+
+            let x = (col as u32 * CELL_WIDTH) as i32 + offset_x;
+            let y = (row as u32 * CELL_HEIGHT) as i32;
+
+            let role = roles
+                .get(row)
+                .and_then(|r| r.get(col))
+                .copied()
+                .unwrap_or(CellRole::Text);
+            let color = to_rgba(palette.foreground(role), FOREGROUND);
+
+            imageproc::drawing::draw_text_mut(&mut img, color, x, y, scale, font, cell);
+        }
+    }
+
+    img
+}
+
+pub struct Screenshot {
+    dir: PathBuf,
+}
+
+impl Screenshot {
+    pub fn new(dir: &str) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn get_dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn set_dir(&mut self, dir: &str) {
+        self.dir = dir.into();
+    }
+
+    // saves the buffer in `palette`'s colors as <dir>/invader-<timestamp>.png
+    // and returns the path
+    pub fn save(&self, game_buffer: &GameBuffer, palette: Palette) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)?;
+
+        let file_name = format!("invader-{}.png", Timestamp::now().file_stamp());
+        let path = self.dir.join(file_name);
+
+        rasterize(game_buffer, palette).save(&path)?;
+
+        Ok(path)
+    }
+}
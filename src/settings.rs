@@ -0,0 +1,358 @@
+use crossterm::event::KeyCode;
+
+use crate::config::Config;
+use crate::game::Difficulty;
+use crate::keyboard::key_name;
+use crate::locale;
+use crate::palette::Palette;
+use crate::screen::Menu;
+use crate::starfield::StarDensity;
+use crate::theme::Theme;
+
+const FRAME_RATES: [u32; 4] = [30, 60, 100, 144];
+const MAX_STARTING_LIVES: usize = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Setting {
+    Volume,
+    SfxVolume,
+    MusicVolume,
+    Mute,
+    Stereo,
+    Difficulty,
+    StartingLives,
+    FrameRate,
+    Theme,
+    Starfield,
+    StarDensity,
+    Language,
+    Palette,
+    ReducedMotion,
+    AudioCues,
+    BindLeft,
+    BindRight,
+    BindFire,
+    BindPause,
+    BindScreenshot,
+    BindRecord,
+    BindMute,
+    Back,
+}
+
+const SETTINGS: [Setting; 23] = [
+    Setting::Volume,
+    Setting::SfxVolume,
+    Setting::MusicVolume,
+    Setting::Mute,
+    Setting::Stereo,
+    Setting::Difficulty,
+    Setting::StartingLives,
+    Setting::FrameRate,
+    Setting::Theme,
+    Setting::Starfield,
+    Setting::StarDensity,
+    Setting::Language,
+    Setting::Palette,
+    Setting::ReducedMotion,
+    Setting::AudioCues,
+    Setting::BindLeft,
+    Setting::BindRight,
+    Setting::BindFire,
+    Setting::BindPause,
+    Setting::BindScreenshot,
+    Setting::BindRecord,
+    Setting::BindMute,
+    Setting::Back,
+];
+
+// 10% steps between 0 and 100%
+fn step_volume(volume: f32, forward: bool) -> f32 {
+    let step = if forward { 0.1 } else { -0.1 };
+    (((volume + step) * 10.0).round() / 10.0).clamp(0.0, 1.0)
+}
+
+// edits a copy of the config, the caller saves and applies it on the way out
+pub struct SettingsMenu {
+    config: Config,
+    menu: Menu,
+    themes: Vec<String>,
+
+    // waiting for the key to bind to the selected entry
+    rebinding: bool,
+}
+
+impl SettingsMenu {
+    pub fn new(config: &Config) -> Self {
+        let mut settings = Self {
+            config: config.clone(),
+            menu: Menu::new(&[]),
+            themes: Theme::available(),
+            rebinding: false,
+        };
+
+        settings.refresh();
+        settings
+    }
+
+    pub fn get_config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: &Config) {
+        self.config = config.clone();
+        self.menu.set_selected(0);
+        self.themes = Theme::available();
+        self.rebinding = false;
+        self.refresh();
+    }
+
+    pub fn get_menu(&self) -> &Menu {
+        &self.menu
+    }
+
+    pub fn is_rebinding(&self) -> bool {
+        self.rebinding
+    }
+
+    fn selected(&self) -> Setting {
+        SETTINGS[self.menu.get_selected()]
+    }
+
+    fn binding_mut(&mut self, setting: Setting) -> Option<&mut String> {
+        let bindings = &mut self.config.key_bindings;
+
+        match setting {
+            Setting::BindLeft => Some(&mut bindings.left),
+            Setting::BindRight => Some(&mut bindings.right),
+            Setting::BindFire => Some(&mut bindings.fire),
+            Setting::BindPause => Some(&mut bindings.pause),
+            Setting::BindScreenshot => Some(&mut bindings.screenshot),
+            Setting::BindRecord => Some(&mut bindings.record),
+            Setting::BindMute => Some(&mut bindings.mute),
+            _ => None,
+        }
+    }
+
+    fn label(&self, setting: Setting) -> String {
+        let config = &self.config;
+        let bindings = &config.key_bindings;
+
+        let key = |name: &str| vec![("key", name.to_string())];
+        let volume = |volume: f32| vec![("volume", format!("{:.0}", volume * 100.0))];
+        let on_off = |on: bool| {
+            let value = if on { "settings.on" } else { "settings.off" };
+            vec![("value", locale::text(value))]
+        };
+
+        match setting {
+            Setting::Volume => locale::text_with("settings.volume", &volume(config.volume)),
+            Setting::SfxVolume => {
+                locale::text_with("settings.sfx_volume", &volume(config.sfx_volume))
+            }
+            Setting::MusicVolume => {
+                locale::text_with("settings.music_volume", &volume(config.music_volume))
+            }
+            Setting::Mute => locale::text_with("settings.mute", &on_off(config.muted)),
+            Setting::Stereo => locale::text_with("settings.stereo", &on_off(config.stereo)),
+            Setting::Difficulty => locale::text_with(
+                "settings.difficulty",
+                &[("difficulty", config.difficulty.get_name())],
+            ),
+            Setting::StartingLives => locale::text_with(
+                "settings.starting_lives",
+                &[("lives", config.starting_lives.to_string())],
+            ),
+            Setting::FrameRate => locale::text_with(
+                "settings.frame_rate",
+                &[("rate", config.frame_rate.to_string())],
+            ),
+            Setting::Theme => {
+                locale::text_with("settings.theme", &[("theme", config.theme.clone())])
+            }
+            Setting::Starfield => {
+                locale::text_with("settings.starfield", &on_off(config.starfield))
+            }
+            Setting::StarDensity => locale::text_with(
+                "settings.star_density",
+                &[("density", config.star_density.get_name())],
+            ),
+            Setting::Language => {
+                let language = match config.language.as_str() {
+                    locale::AUTO => locale::text("settings.language_auto"),
+                    language => locale::language_name(language),
+                };
+                locale::text_with("settings.language", &[("language", language)])
+            }
+            Setting::Palette => locale::text_with(
+                "settings.palette",
+                &[("palette", config.palette.get_name())],
+            ),
+            Setting::ReducedMotion => {
+                locale::text_with("settings.reduced_motion", &on_off(config.reduced_motion))
+            }
+            Setting::AudioCues => {
+                locale::text_with("settings.audio_cues", &on_off(config.audio_cues))
+            }
+            Setting::BindLeft => locale::text_with("settings.bind_left", &key(&bindings.left)),
+            Setting::BindRight => locale::text_with("settings.bind_right", &key(&bindings.right)),
+            Setting::BindFire => locale::text_with("settings.bind_fire", &key(&bindings.fire)),
+            Setting::BindPause => locale::text_with("settings.bind_pause", &key(&bindings.pause)),
+            Setting::BindScreenshot => {
+                locale::text_with("settings.bind_screenshot", &key(&bindings.screenshot))
+            }
+            Setting::BindRecord => {
+                locale::text_with("settings.bind_record", &key(&bindings.record))
+            }
+            Setting::BindMute => locale::text_with("settings.bind_mute", &key(&bindings.mute)),
+            Setting::Back => locale::text("settings.back"),
+        }
+    }
+
+    fn refresh(&mut self) {
+        let mut items: Vec<String> = SETTINGS.iter().map(|s| self.label(*s)).collect();
+
+        if self.rebinding {
+            let selected = self.menu.get_selected();
+            items[selected] = locale::text("settings.press_key");
+        }
+
+        self.menu.set_items(&items);
+    }
+
+    // steps the selected value, `forward` is false for the Left key
+    fn change(&mut self, forward: bool) {
+        let setting = self.selected();
+        let config = &mut self.config;
+
+        match setting {
+            Setting::Volume => config.volume = step_volume(config.volume, forward),
+            Setting::SfxVolume => config.sfx_volume = step_volume(config.sfx_volume, forward),
+            Setting::MusicVolume => config.music_volume = step_volume(config.music_volume, forward),
+            Setting::Mute => config.muted = !config.muted,
+            Setting::Stereo => config.stereo = !config.stereo,
+            Setting::Difficulty => {
+                let all = Difficulty::all();
+                let current = all
+                    .iter()
+                    .position(|d| *d == config.difficulty)
+                    .unwrap_or(0);
+                let next = if forward {
+                    (current + 1) % all.len()
+                } else {
+                    (current + all.len() - 1) % all.len()
+                };
+                config.difficulty = all[next];
+            }
+            Setting::StartingLives => {
+                if forward {
+                    config.starting_lives = (config.starting_lives + 1).min(MAX_STARTING_LIVES);
+                } else {
+                    config.starting_lives = config.starting_lives.saturating_sub(1).max(1);
+                }
+            }
+            Setting::FrameRate => {
+                let current = FRAME_RATES
+                    .iter()
+                    .position(|r| *r >= config.frame_rate)
+                    .unwrap_or(FRAME_RATES.len() - 1);
+                let next = if forward {
+                    (current + 1).min(FRAME_RATES.len() - 1)
+                } else {
+                    current.saturating_sub(1)
+                };
+                config.frame_rate = FRAME_RATES[next];
+            }
+            Setting::Theme => {
+                let themes = &self.themes;
+                let current = themes.iter().position(|t| *t == config.theme).unwrap_or(0);
+                let next = if forward {
+                    (current + 1) % themes.len()
+                } else {
+                    (current + themes.len() - 1) % themes.len()
+                };
+                config.theme = themes[next].clone();
+            }
+            Setting::Starfield => config.starfield = !config.starfield,
+            Setting::StarDensity => {
+                let all = StarDensity::all();
+                let current = all
+                    .iter()
+                    .position(|d| *d == config.star_density)
+                    .unwrap_or(0);
+                let next = if forward {
+                    (current + 1) % all.len()
+                } else {
+                    (current + all.len() - 1) % all.len()
+                };
+                config.star_density = all[next];
+            }
+            Setting::Language => {
+                let languages = locale::languages();
+                let current = languages
+                    .iter()
+                    .position(|l| *l == config.language)
+                    .unwrap_or(0);
+                let next = if forward {
+                    (current + 1) % languages.len()
+                } else {
+                    (current + languages.len() - 1) % languages.len()
+                };
+                config.language = languages[next].clone();
+            }
+            Setting::Palette => {
+                let all = Palette::all();
+                let current = all.iter().position(|p| *p == config.palette).unwrap_or(0);
+                let next = if forward {
+                    (current + 1) % all.len()
+                } else {
+                    (current + all.len() - 1) % all.len()
+                };
+                config.palette = all[next];
+            }
+            Setting::ReducedMotion => config.reduced_motion = !config.reduced_motion,
+            Setting::AudioCues => config.audio_cues = !config.audio_cues,
+            _ => (),
+        }
+    }
+
+    // returns true once the player leaves the settings screen
+    pub fn handle(&mut self, code: KeyCode) -> bool {
+        if self.rebinding {
+            self.rebinding = false;
+
+            let setting = self.selected();
+            if code != KeyCode::Esc {
+                if let Some(binding) = self.binding_mut(setting) {
+                    *binding = key_name(code);
+                }
+            }
+
+            self.refresh();
+            return false;
+        }
+
+        let mut leave = false;
+
+        match code {
+            KeyCode::Up => self.menu.select_previous(),
+            KeyCode::Down => self.menu.select_next(),
+            KeyCode::Left => self.change(false),
+            KeyCode::Right => self.change(true),
+            KeyCode::Esc => leave = true,
+            KeyCode::Enter => match self.selected() {
+                Setting::Back => leave = true,
+                setting => {
+                    if self.binding_mut(setting).is_some() {
+                        self.rebinding = true;
+                    } else {
+                        self.change(true);
+                    }
+                }
+            },
+            _ => (),
+        }
+
+        self.refresh();
+        leave
+    }
+}
@@ -1,8 +1,10 @@
 use std::ops::{Deref, DerefMut};
 
+use crate::animation::Animation;
 use crate::audio::GameObjectSound;
 use crate::container::{Container, Direction, Point};
 use crate::gobj::GameObject;
+use crate::sounds::SoundEvent;
 use crate::weapon::Bullet;
 
 use anyhow::Result;
@@ -12,8 +14,11 @@ pub struct Ship {
     direction: Direction,
     speed: usize,
     length: usize,
+    // message key of the weapon's name, translated when it is drawn
+    weapon: &'static str,
 
     fire_sound: GameObjectSound,
+    on_hit_sound: GameObjectSound,
 }
 
 impl Deref for Ship {
@@ -31,19 +36,28 @@ impl DerefMut for Ship {
 }
 
 impl Ship {
-    pub fn new(x: usize, y: usize, length: usize, speed: usize) -> Self {
+    // `frames` are the theme's ship glyphs
+    pub fn new(x: usize, y: usize, length: usize, speed: usize, frames: &[String]) -> Self {
         let container = Container::new(Point { x, y }, Point { x: x + length, y });
-        let symbol = "⌬";
-        let gobj = GameObject::new(container, symbol);
+        let frames: Vec<&str> = frames.iter().map(|f| f.as_str()).collect();
 
-        let fire_sound = GameObjectSound::new("assets/sounds/on_hit.mp3");
+        let mut gobj = GameObject::new(container, frames[0]);
+        gobj.set_animation(Animation::timed(
+            &frames,
+            std::time::Duration::from_millis(150),
+        ));
+
+        let fire_sound = GameObjectSound::new(SoundEvent::PlayerFire);
+        let on_hit_sound = GameObjectSound::new(SoundEvent::ShipDestroyed);
 
         Self {
             gobj,
             direction: Direction::RIGHT,
             speed,
             length,
+            weapon: "weapon.laser_cannon",
             fire_sound,
+            on_hit_sound,
         }
     }
 
@@ -77,4 +91,22 @@ impl Ship {
     pub fn get_length(&self) -> usize {
         self.length
     }
+
+    pub fn get_weapon(&self) -> &str {
+        self.weapon
+    }
+
+    // the sounds are heard from where the ship is in `area`
+    pub fn fire(&self, area: &Container) -> Result<()> {
+        self.fire_sound.play_at(self.get_middle_x(), area)
+    }
+
+    pub fn destroy(&mut self, area: &Container) -> Result<()> {
+        self.gobj.destroy();
+        self.on_hit_sound.play_at(self.get_middle_x(), area)
+    }
+
+    fn get_middle_x(&self) -> usize {
+        self.get_container().top.x + self.length / 2
+    }
 }
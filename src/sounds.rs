@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow as error, Result};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_MANIFEST: &str = include_str!("../assets/sounds.json");
+
+// everything in the game that makes a sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SoundEvent {
+    PlayerFire,
+    EnemyFire,
+    AlienDestroyed,
+    ShipDestroyed,
+    // a life is gone but the game goes on
+    LifeLost,
+    WaveStart,
+    WaveClear,
+    GameOver,
+    // the ship ran into the side of the playfield
+    Edge,
+    MenuMove,
+    MenuSelect,
+    MenuBack,
+}
+
+impl SoundEvent {
+    pub fn all() -> Vec<SoundEvent> {
+        vec![
+            SoundEvent::PlayerFire,
+            SoundEvent::EnemyFire,
+            SoundEvent::AlienDestroyed,
+            SoundEvent::ShipDestroyed,
+            SoundEvent::LifeLost,
+            SoundEvent::WaveStart,
+            SoundEvent::WaveClear,
+            SoundEvent::GameOver,
+            SoundEvent::Edge,
+            SoundEvent::MenuMove,
+            SoundEvent::MenuSelect,
+            SoundEvent::MenuBack,
+        ]
+    }
+
+    // the key in the manifest, e.g. "enemy_fire"
+    pub fn get_name(&self) -> &str {
+        match self {
+            SoundEvent::PlayerFire => "player_fire",
+            SoundEvent::EnemyFire => "enemy_fire",
+            SoundEvent::AlienDestroyed => "alien_destroyed",
+            SoundEvent::ShipDestroyed => "ship_destroyed",
+            SoundEvent::LifeLost => "life_lost",
+            SoundEvent::WaveStart => "wave_start",
+            SoundEvent::WaveClear => "wave_clear",
+            SoundEvent::GameOver => "game_over",
+            SoundEvent::Edge => "edge",
+            SoundEvent::MenuMove => "menu_move",
+            SoundEvent::MenuSelect => "menu_select",
+            SoundEvent::MenuBack => "menu_back",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<SoundEvent> {
+        Self::all().into_iter().find(|e| e.get_name() == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundEntry {
+    // a sound file or a synth effect, e.g. "synth:laser"
+    pub sound: String,
+    // scales the effects channel, 0.0 to 1.0
+    pub volume: f32,
+    // the event stays silent this long after it last played, so a burst of
+    // shots or key repeats doesn't turn into noise
+    pub cooldown_ms: u64,
+}
+
+impl Default for SoundEntry {
+    fn default() -> Self {
+        Self {
+            sound: String::new(),
+            volume: 1.0,
+            cooldown_ms: 0,
+        }
+    }
+}
+
+impl SoundEntry {
+    pub fn get_cooldown(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.cooldown_ms)
+    }
+}
+
+// which sound every event plays; events missing from it are silent
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundManifest {
+    entries: BTreeMap<SoundEvent, SoundEntry>,
+}
+
+impl Default for SoundManifest {
+    fn default() -> Self {
+        Self::parse(DEFAULT_MANIFEST).expect("Bundled sound manifest is invalid")
+    }
+}
+
+impl SoundManifest {
+    pub fn parse(data: &str) -> Result<Self> {
+        let entries = serde_json::from_str(data)?;
+
+        Ok(Self { entries })
+    }
+
+    pub fn get(&self, event: SoundEvent) -> Option<&SoundEntry> {
+        self.entries.get(&event)
+    }
+
+    pub fn get_entries(&self) -> &BTreeMap<SoundEvent, SoundEntry> {
+        &self.entries
+    }
+
+    // config changes by event name, field by field, e.g.
+    // {"enemy_fire": {"volume": 0.3}} only makes enemy shots quieter;
+    // returns one error per change that was ignored
+    pub fn apply_overrides(
+        &mut self,
+        overrides: &BTreeMap<String, serde_json::Value>,
+    ) -> Vec<anyhow::Error> {
+        let mut errors = vec![];
+
+        for (name, changes) in overrides {
+            if let Err(e) = self.apply_override(name, changes) {
+                errors.push(e);
+            }
+        }
+
+        errors
+    }
+
+    fn apply_override(&mut self, name: &str, changes: &serde_json::Value) -> Result<()> {
+        let event = SoundEvent::from_name(name).ok_or(error!("Unknown sound event: {}", name))?;
+        let entry = self.entries.entry(event).or_default();
+
+        let mut fields = serde_json::to_value(&*entry)?;
+        if let (Some(fields), serde_json::Value::Object(changes)) =
+            (fields.as_object_mut(), changes)
+        {
+            for (field, value) in changes {
+                fields.insert(field.clone(), value.clone());
+            }
+        }
+
+        *entry = serde_json::from_value(fields).map_err(|e| error!("Sound {}: {}", name, e))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,284 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::container::{Container, Point};
+use crate::locale;
+use crate::theme::Theme;
+
+// rows per second the farthest layer scrolls, every closer layer is faster
+const BASE_SPEED: f32 = 1.5;
+// odds per second that a nebula or planet starts drifting in
+const DECORATION_CHANCE: f64 = 0.03;
+const MAX_DECORATIONS: usize = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum StarDensity {
+    Low,
+    Medium,
+    High,
+}
+
+impl StarDensity {
+    pub fn all() -> Vec<StarDensity> {
+        vec![StarDensity::Low, StarDensity::Medium, StarDensity::High]
+    }
+
+    pub fn get_name(&self) -> String {
+        match self {
+            StarDensity::Low => locale::text("star_density.low"),
+            StarDensity::Medium => locale::text("star_density.medium"),
+            StarDensity::High => locale::text("star_density.high"),
+        }
+    }
+
+    // share of the playfield cells that hold a star
+    pub fn get_fill(&self) -> f32 {
+        match self {
+            StarDensity::Low => 0.01,
+            StarDensity::Medium => 0.025,
+            StarDensity::High => 0.05,
+        }
+    }
+}
+
+// purely visual, like particles the background never collides with anything
+#[derive(Debug, Clone)]
+pub struct Star {
+    x: usize,
+    y: f32,
+    // 0 is the farthest and slowest layer
+    layer: usize,
+}
+
+impl Star {
+    pub fn get_pos(&self) -> Point {
+        Point {
+            x: self.x,
+            y: self.y as usize,
+        }
+    }
+
+    pub fn get_layer(&self) -> usize {
+        self.layer
+    }
+}
+
+// a nebula or planet, drifts slower than the farthest stars
+#[derive(Debug, Clone)]
+pub struct Decoration {
+    x: usize,
+    // negative while it is still sliding in over the top edge
+    y: f32,
+    rows: Vec<String>,
+}
+
+impl Decoration {
+    pub fn get_x(&self) -> usize {
+        self.x
+    }
+
+    // the topmost row, may lie above the playfield
+    pub fn get_y(&self) -> isize {
+        self.y.floor() as isize
+    }
+
+    pub fn get_rows(&self) -> &Vec<String> {
+        &self.rows
+    }
+}
+
+pub struct Starfield {
+    stars: Vec<Star>,
+    decorations: Vec<Decoration>,
+    bounds: Container,
+    density: StarDensity,
+
+    // off for low-bandwidth terminals, nothing is drawn or ticked
+    enabled: bool,
+    // reduced motion keeps the stars still
+    scrolling: bool,
+
+    // one glyph per layer, far to near
+    layer_glyphs: Vec<String>,
+    decoration_art: Vec<Vec<String>>,
+
+    last_tick: std::time::Instant,
+}
+
+impl Starfield {
+    pub fn new(bounds: &Container, density: StarDensity) -> Self {
+        let mut starfield = Self {
+            stars: vec![],
+            decorations: vec![],
+            bounds: bounds.clone(),
+            density,
+            enabled: true,
+            scrolling: true,
+            layer_glyphs: vec![],
+            decoration_art: vec![],
+            last_tick: std::time::Instant::now(),
+        };
+
+        starfield.set_theme(&Theme::default());
+        starfield
+    }
+
+    pub fn set_theme(&mut self, theme: &Theme) {
+        let layers = self.layer_glyphs.len();
+
+        self.layer_glyphs = theme.stars.clone();
+        self.decoration_art = theme.decorations.clone();
+        self.decorations.clear();
+
+        // a theme with a different number of layers needs a new sky
+        if layers != self.layer_glyphs.len() {
+            self.populate();
+        }
+    }
+
+    pub fn get_stars(&self) -> &Vec<Star> {
+        &self.stars
+    }
+
+    pub fn get_decorations(&self) -> &Vec<Decoration> {
+        &self.decorations
+    }
+
+    pub fn get_glyph(&self, star: &Star) -> &str {
+        &self.layer_glyphs[star.layer.min(self.layer_glyphs.len() - 1)]
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.last_tick = std::time::Instant::now();
+    }
+
+    pub fn is_scrolling(&self) -> bool {
+        self.scrolling
+    }
+
+    pub fn set_scrolling(&mut self, scrolling: bool) {
+        self.scrolling = scrolling;
+        self.last_tick = std::time::Instant::now();
+
+        if !scrolling {
+            self.decorations.clear();
+        }
+    }
+
+    pub fn get_density(&self) -> StarDensity {
+        self.density
+    }
+
+    pub fn set_density(&mut self, density: StarDensity) {
+        if self.density != density {
+            self.density = density;
+            self.populate();
+        }
+    }
+
+    // the area between the playfield borders the stars fill
+    pub fn set_bounds(&mut self, bounds: &Container) {
+        self.bounds = bounds.clone();
+        self.decorations.clear();
+        self.populate();
+    }
+
+    fn top(&self) -> usize {
+        self.bounds.top.y + 1
+    }
+
+    fn random_x(&self, rng: &mut impl Rng) -> usize {
+        rng.gen_range(self.bounds.top.x + 1..self.bounds.bottom.x.max(self.bounds.top.x + 2))
+    }
+
+    // scatters a fresh set of stars over the whole area
+    fn populate(&mut self) {
+        let mut rng = rand::thread_rng();
+
+        let top = self.top();
+        let bottom = self.bounds.bottom.y.max(top + 1);
+        let cells = self.bounds.get_width() * (bottom - top);
+        let count = (cells as f32 * self.density.get_fill()) as usize;
+        let layers = self.layer_glyphs.len().max(1);
+
+        self.stars = (0..count)
+            .map(|_| Star {
+                x: self.random_x(&mut rng),
+                y: rng.gen_range(top..bottom) as f32,
+                layer: rng.gen_range(0..layers),
+            })
+            .collect();
+    }
+
+    fn layer_speed(layer: usize) -> f32 {
+        BASE_SPEED * (layer + 1) as f32
+    }
+
+    fn spawn_decoration(&mut self, rng: &mut impl Rng) {
+        if self.decoration_art.is_empty() || self.decorations.len() >= MAX_DECORATIONS {
+            return;
+        }
+
+        let art = &self.decoration_art[rng.gen_range(0..self.decoration_art.len())];
+        let width = art
+            .iter()
+            .map(|row| locale::text_width(row))
+            .max()
+            .unwrap_or(0);
+
+        let left = self.bounds.top.x + 1;
+        let right = self.bounds.bottom.x.saturating_sub(width).max(left + 1);
+
+        self.decorations.push(Decoration {
+            x: rng.gen_range(left..right),
+            y: self.top() as f32 - art.len() as f32,
+            rows: art.clone(),
+        });
+    }
+
+    // scrolls every layer down, stars leaving the bottom come back at the top
+    pub fn tick(&mut self) {
+        let now = std::time::Instant::now();
+        let dt = (now - self.last_tick).as_secs_f32();
+        self.last_tick = now;
+
+        if !self.enabled || !self.scrolling {
+            return;
+        }
+
+        let mut rng = rand::thread_rng();
+        let top = self.top() as f32;
+        let bottom = self.bounds.bottom.y as f32;
+
+        for i in 0..self.stars.len() {
+            let star = &mut self.stars[i];
+            star.y += Self::layer_speed(star.layer) * dt;
+
+            if star.y >= bottom {
+                let x = self.random_x(&mut rng);
+                let star = &mut self.stars[i];
+                star.y = top + (star.y - bottom) % (bottom - top).max(1.0);
+                star.x = x;
+            }
+        }
+
+        let speed = Self::layer_speed(0) / 2.0;
+        for d in &mut self.decorations {
+            d.y += speed * dt;
+        }
+        self.decorations.retain(|d| d.y < bottom);
+
+        if rng.gen_bool((DECORATION_CHANCE * dt as f64).min(1.0)) {
+            self.spawn_decoration(&mut rng);
+        }
+    }
+
+    pub fn shift_timers(&mut self, by: std::time::Duration) {
+        self.last_tick += by;
+    }
+}
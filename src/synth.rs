@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow as error, Result};
+use rodio::Source;
+use serde::{Deserialize, Serialize};
+
+pub const SAMPLE_RATE: u32 = 44100;
+
+// sound names starting with this are synthesized instead of read from a file,
+// e.g. "synth:laser"
+pub const PREFIX: &str = "synth:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Waveform {
+    Square,
+    Triangle,
+    // random levels held for one period, pitched like the NES noise channel
+    Noise,
+}
+
+// everything an effect is made of, presets can be overridden field by field
+// in the config
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SynthParams {
+    pub waveform: Waveform,
+    // Hz, slides from `frequency` to `end_frequency` over the duration
+    pub frequency: f32,
+    pub end_frequency: f32,
+    pub duration_ms: u64,
+    pub attack_ms: u64,
+    // share of a square wave period spent high
+    pub duty: f32,
+    // warble, wobbles the frequency `vibrato_rate` times a second by up to
+    // `vibrato_depth` of itself
+    pub vibrato_rate: f32,
+    pub vibrato_depth: f32,
+    pub volume: f32,
+}
+
+impl Default for SynthParams {
+    fn default() -> Self {
+        Self {
+            waveform: Waveform::Square,
+            frequency: 440.0,
+            end_frequency: 440.0,
+            duration_ms: 200,
+            attack_ms: 5,
+            duty: 0.5,
+            vibrato_rate: 0.0,
+            vibrato_depth: 0.0,
+            volume: 0.4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Preset {
+    Laser,
+    // the aliens' shots
+    Zap,
+    Explosion,
+    Ufo,
+    March,
+    // menu cursor
+    Blip,
+    Confirm,
+    // rising, a cleared wave
+    Fanfare,
+    // falling, a lost life
+    Descend,
+}
+
+impl Preset {
+    pub fn all() -> Vec<Preset> {
+        vec![
+            Preset::Laser,
+            Preset::Zap,
+            Preset::Explosion,
+            Preset::Ufo,
+            Preset::March,
+            Preset::Blip,
+            Preset::Confirm,
+            Preset::Fanfare,
+            Preset::Descend,
+        ]
+    }
+
+    pub fn get_name(&self) -> &str {
+        match self {
+            Preset::Laser => "laser",
+            Preset::Zap => "zap",
+            Preset::Explosion => "explosion",
+            Preset::Ufo => "ufo",
+            Preset::March => "march",
+            Preset::Blip => "blip",
+            Preset::Confirm => "confirm",
+            Preset::Fanfare => "fanfare",
+            Preset::Descend => "descend",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Preset> {
+        Self::all().into_iter().find(|p| p.get_name() == name)
+    }
+
+    // e.g. "synth:laser"
+    pub fn get_sound(&self) -> String {
+        format!("{}{}", PREFIX, self.get_name())
+    }
+
+    pub fn get_params(&self) -> SynthParams {
+        let defaults = SynthParams::default();
+
+        match self {
+            Preset::Laser => SynthParams {
+                frequency: 1400.0,
+                end_frequency: 250.0,
+                duration_ms: 160,
+                duty: 0.25,
+                volume: 0.3,
+                ..defaults
+            },
+            Preset::Zap => SynthParams {
+                frequency: 500.0,
+                end_frequency: 120.0,
+                duration_ms: 120,
+                volume: 0.25,
+                ..defaults
+            },
+            Preset::Explosion => SynthParams {
+                waveform: Waveform::Noise,
+                frequency: 3000.0,
+                end_frequency: 150.0,
+                duration_ms: 450,
+                volume: 0.5,
+                ..defaults
+            },
+            Preset::Ufo => SynthParams {
+                waveform: Waveform::Triangle,
+                frequency: 700.0,
+                end_frequency: 700.0,
+                duration_ms: 600,
+                attack_ms: 40,
+                vibrato_rate: 9.0,
+                vibrato_depth: 0.25,
+                ..defaults
+            },
+            Preset::March => SynthParams {
+                frequency: 70.0,
+                end_frequency: 50.0,
+                duration_ms: 110,
+                attack_ms: 2,
+                volume: 0.6,
+                ..defaults
+            },
+            Preset::Blip => SynthParams {
+                frequency: 1200.0,
+                end_frequency: 1200.0,
+                duration_ms: 40,
+                attack_ms: 2,
+                duty: 0.25,
+                volume: 0.2,
+                ..defaults
+            },
+            Preset::Confirm => SynthParams {
+                frequency: 800.0,
+                end_frequency: 1600.0,
+                duration_ms: 90,
+                attack_ms: 2,
+                volume: 0.25,
+                ..defaults
+            },
+            Preset::Fanfare => SynthParams {
+                waveform: Waveform::Triangle,
+                frequency: 400.0,
+                end_frequency: 1200.0,
+                duration_ms: 700,
+                attack_ms: 20,
+                vibrato_rate: 6.0,
+                vibrato_depth: 0.05,
+                volume: 0.5,
+                ..defaults
+            },
+            Preset::Descend => SynthParams {
+                frequency: 600.0,
+                end_frequency: 90.0,
+                duration_ms: 650,
+                attack_ms: 10,
+                vibrato_rate: 12.0,
+                vibrato_depth: 0.08,
+                volume: 0.4,
+                ..defaults
+            },
+        }
+    }
+}
+
+// plays one effect, mono f32 samples
+#[derive(Debug, Clone)]
+pub struct Synth {
+    params: SynthParams,
+    length: u64,
+    position: u64,
+
+    phase: f32,
+    noise: u32,
+    noise_level: f32,
+}
+
+impl Synth {
+    pub fn new(params: &SynthParams) -> Self {
+        let length = params.duration_ms * SAMPLE_RATE as u64 / 1000;
+
+        Self {
+            params: *params,
+            length,
+            position: 0,
+            phase: 0.0,
+            noise: 0x1234_5678,
+            noise_level: 0.0,
+        }
+    }
+
+    fn next_noise(&mut self) -> f32 {
+        // xorshift, plenty random for a hiss
+        self.noise ^= self.noise << 13;
+        self.noise ^= self.noise >> 17;
+        self.noise ^= self.noise << 5;
+
+        (self.noise as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+
+    fn envelope(&self, t: f32) -> f32 {
+        let attack = self.params.attack_ms as f32 / 1000.0;
+        let duration = self.params.duration_ms as f32 / 1000.0;
+
+        if t < attack {
+            t / attack
+        } else {
+            (1.0 - (t - attack) / (duration - attack).max(0.001)).max(0.0)
+        }
+    }
+}
+
+impl Iterator for Synth {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.position >= self.length {
+            return None;
+        }
+
+        let progress = self.position as f32 / self.length as f32;
+        let t = self.position as f32 / SAMPLE_RATE as f32;
+        self.position += 1;
+
+        let params = self.params;
+        let mut frequency = params.frequency + (params.end_frequency - params.frequency) * progress;
+        if params.vibrato_rate > 0.0 {
+            let wobble = (t * params.vibrato_rate * std::f32::consts::TAU).sin();
+            frequency *= 1.0 + wobble * params.vibrato_depth;
+        }
+
+        let phase = self.phase + frequency / SAMPLE_RATE as f32;
+        let wrapped = phase >= 1.0;
+        self.phase = phase.fract();
+
+        let sample = match params.waveform {
+            Waveform::Square => {
+                if self.phase < params.duty {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Triangle => 1.0 - 4.0 * (self.phase - 0.5).abs(),
+            Waveform::Noise => {
+                if wrapped {
+                    self.noise_level = self.next_noise();
+                }
+                self.noise_level
+            }
+        };
+
+        Some(sample * self.envelope(t) * params.volume)
+    }
+}
+
+impl Source for Synth {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<std::time::Duration> {
+        Some(std::time::Duration::from_millis(self.params.duration_ms))
+    }
+}
+
+// renders `params` into a buffer, an effect is short enough to keep whole
+pub fn render(params: &SynthParams) -> Result<Vec<f32>> {
+    if params.duration_ms == 0 || params.frequency <= 0.0 || params.end_frequency <= 0.0 {
+        return Err(error!("Synth needs a duration and positive frequencies"));
+    }
+
+    Ok(Synth::new(params).collect())
+}
+
+// the effect called `name`: a preset, or a new effect defined in the config,
+// with the fields set in `overrides` replaced, e.g.
+// {"laser": {"frequency": 900.0}} only changes the laser's pitch
+pub fn get_params(
+    name: &str,
+    overrides: &BTreeMap<String, serde_json::Value>,
+) -> Result<SynthParams> {
+    let base = match Preset::from_name(name) {
+        Some(preset) => preset.get_params(),
+        None if overrides.contains_key(name) => SynthParams::default(),
+        None => return Err(error!("Unknown synth effect: {}", name)),
+    };
+
+    let mut params = serde_json::to_value(base)?;
+    if let (Some(fields), Some(serde_json::Value::Object(changes))) =
+        (params.as_object_mut(), overrides.get(name))
+    {
+        for (field, value) in changes {
+            fields.insert(field.clone(), value.clone());
+        }
+    }
+
+    serde_json::from_value(params).map_err(|e| error!("Synth effect {}: {}", name, e))
+}
@@ -1,10 +1,32 @@
-use ab_glyph::{FontRef, PxScale};
-use image::GrayImage;
+use ab_glyph::{Font, FontRef, PxScale};
 
 use crate::container::Container;
 
 pub type Image2d = Vec<Vec<u8>>;
 
+// one entry per terminal cell, true where the banner has ink
+pub type GlyphGrid = Vec<Vec<bool>>;
+
+// large enough that the downscale to terminal cells keeps the letter shapes
+const RENDER_FONT_SIZE: f32 = 64.0;
+
+#[derive(Debug, Clone, Copy)]
+pub enum BannerFont {
+    DejaVuSans,
+    AllAges,
+}
+
+impl BannerFont {
+    pub fn load(&self) -> FontRef<'static> {
+        let data: &'static [u8] = match self {
+            BannerFont::DejaVuSans => include_bytes!("../assets/fonts/DejaVuSans.ttf"),
+            BannerFont::AllAges => include_bytes!("../assets/fonts/AllAgesDEMO.ttf"),
+        };
+
+        FontRef::try_from_slice(data).expect("Bundled font is invalid")
+    }
+}
+
 pub fn image_to_vec(img: &image::GrayImage) -> Image2d {
     let mut result: Image2d = vec![];
 
@@ -21,57 +43,79 @@ pub fn image_to_vec(img: &image::GrayImage) -> Image2d {
     result
 }
 
-pub fn process_text(canvas_container: Container) -> Image2d {
-    let font = FontRef::try_from_slice(include_bytes!("../assets/fonts/DejaVuSans.ttf")).unwrap();
-
-    let font_size: f32 = 8.0;
-
-    let scale = PxScale {
-        x: font_size,
-        y: font_size,
-    };
+fn crop_to_ink(img: &image::GrayImage) -> Option<image::GrayImage> {
+    let mut bounds: Option<(u32, u32, u32, u32)> = None;
 
-    let scale2 = PxScale {
-        x: font_size / 1.1,
-        y: font_size / 1.1,
-    };
+    for (x, y, pix) in img.enumerate_pixels() {
+        if pix.0[0] == 0 {
+            continue;
+        }
 
-    let width: u32 = 128u32;
-    let height: u32 = 128u32;
+        bounds = Some(match bounds {
+            Some((x1, y1, x2, y2)) => (x1.min(x), y1.min(y), x2.max(x), y2.max(y)),
+            None => (x, y, x, y),
+        });
+    }
 
-    let mut img = image::GrayImage::new(width, height);
-    //let text = "P L A Y  A G A I N";
-    let text = "P L A Y A G A I N";
-    let text2 = "P R E S S  A N Y  K E Y  T O C O N T I N U E";
+    let (x1, y1, x2, y2) = bounds?;
+    let cropped = image::imageops::crop_imm(img, x1, y1, x2 - x1 + 1, y2 - y1 + 1);
 
-    let (w, h) = imageproc::drawing::text_size(scale, &font, text);
+    Some(cropped.to_image())
+}
 
-    imageproc::drawing::draw_text_mut(&mut img, image::Luma([255u8]), 0, 0, scale, &font, text);
+// rasterizes `text` and scales it down to the largest grid that fits in
+// `target`, a cell is set when its pixel is brighter than `threshold`
+pub fn render_text(text: &str, font: BannerFont, target: &Container, threshold: u8) -> GlyphGrid {
+    let font = font.load();
+
+    // a missing glyph would be drawn as an empty box, leave the title to the caller
+    let missing_glyph = text
+        .chars()
+        .any(|ch| !ch.is_whitespace() && font.glyph_id(ch).0 == 0);
+    if missing_glyph {
+        return vec![];
+    }
 
-    imageproc::drawing::draw_text_mut(
-        &mut img,
-        image::Luma([255u8]),
-        0 as i32,
-        (h * 3u32) as i32,
-        scale2,
-        &font,
-        text2,
-    );
+    let scale = PxScale {
+        x: RENDER_FONT_SIZE,
+        y: RENDER_FONT_SIZE,
+    };
 
-    let (w, h) = imageproc::drawing::text_size(scale, &font, text);
+    let (width, height) = imageproc::drawing::text_size(scale, &font, text);
+    if width == 0 || height == 0 || target.get_width() == 0 || target.get_height() == 0 {
+        return vec![];
+    }
 
-    //for row in 0..img.height() {
-    //    for col in 0..img.width() {
-    //        let pix = img.get_pixel_mut(col, row);
-    //        let val = pix.0[0];
-    //    }
-    //}
+    // glyphs can reach past the reported size, so draw on a larger canvas
+    // and crop to the pixels that were actually touched
+    let mut canvas = image::GrayImage::new(width * 2, height * 2);
+    let (x, y) = ((width / 2) as i32, (height / 2) as i32);
+    imageproc::drawing::draw_text_mut(&mut canvas, image::Luma([255u8]), x, y, scale, &font, text);
 
-    let target_width = canvas_container.get_width() as u32;
-    let target_height = canvas_container.get_height() as u32;
-    let resized =
-        image::imageops::resize(&img, width, height, image::imageops::FilterType::Triangle);
-    resized.save("hello.png").unwrap();
+    let img = match crop_to_ink(&canvas) {
+        Some(img) => img,
+        None => return vec![],
+    };
+    let (width, height) = img.dimensions();
+
+    // terminal cells are about twice as tall as they are wide, so every
+    // row of cells covers two rows worth of square pixels
+    let fit_x = target.get_width() as f32 / width as f32;
+    let fit_y = (target.get_height() * 2) as f32 / height as f32;
+    let fit = fit_x.min(fit_y);
+
+    let target_width = ((width as f32 * fit) as u32).max(1);
+    let target_height = ((height as f32 * fit / 2.0) as u32).max(1);
+
+    let resized = image::imageops::resize(
+        &img,
+        target_width,
+        target_height,
+        image::imageops::FilterType::Triangle,
+    );
 
     image_to_vec(&resized)
+        .iter()
+        .map(|row| row.iter().map(|pix| *pix > threshold).collect())
+        .collect()
 }
@@ -0,0 +1,177 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow as error, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::config_dir;
+
+pub const AUTO: &str = "auto";
+pub const ASCII: &str = "ascii";
+pub const UNICODE: &str = "unicode";
+
+const ASCII_THEME: &str = include_str!("../assets/themes/ascii.json");
+const UNICODE_THEME: &str = include_str!("../assets/themes/unicode.json");
+
+// the glyph for every game object, border and HUD element; lists are
+// animation frames, objects that don't animate only use the first one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Theme {
+    pub name: String,
+
+    pub ship: Vec<String>,
+    pub alien: Vec<String>,
+    pub bullet: Vec<String>,
+    pub enemy_bullet: Vec<String>,
+
+    pub border: String,
+    pub banner: String,
+    pub life: String,
+
+    pub explosion: Vec<String>,
+    pub debris: Vec<String>,
+    pub spark: Vec<String>,
+
+    // background, one star glyph per parallax layer from far to near and
+    // multi-row nebula or planet art; older theme files get the ascii ones
+    #[serde(default = "default_stars")]
+    pub stars: Vec<String>,
+    #[serde(default = "default_decorations")]
+    pub decorations: Vec<Vec<String>>,
+}
+
+fn default_stars() -> Vec<String> {
+    Theme::default().stars
+}
+
+fn default_decorations() -> Vec<Vec<String>> {
+    Theme::default().decorations
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::parse(ASCII_THEME).expect("Bundled ascii theme is invalid")
+    }
+}
+
+impl Theme {
+    pub fn parse(data: &str) -> Result<Self> {
+        let theme: Self = serde_json::from_str(data)?;
+        theme.validate()?;
+
+        Ok(theme)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let frames = [
+            ("ship", &self.ship),
+            ("alien", &self.alien),
+            ("bullet", &self.bullet),
+            ("enemy_bullet", &self.enemy_bullet),
+            ("explosion", &self.explosion),
+            ("debris", &self.debris),
+            ("spark", &self.spark),
+            ("stars", &self.stars),
+        ];
+
+        for (field, list) in frames {
+            if list.is_empty() {
+                return Err(error!(
+                    "Theme {}: {} needs at least one glyph",
+                    self.name, field
+                ));
+            }
+        }
+
+        for (field, glyph) in [
+            ("border", &self.border),
+            ("banner", &self.banner),
+            ("life", &self.life),
+        ] {
+            if glyph.is_empty() {
+                return Err(error!("Theme {}: {} is empty", self.name, field));
+            }
+        }
+
+        Ok(())
+    }
+
+    // user themes live next to the config, e.g. ~/.config/invader/themes/neon.json
+    pub fn dir() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("themes"))
+    }
+
+    // "auto", the bundled themes and every theme file found in `dir`
+    pub fn available() -> Vec<String> {
+        let mut names = vec![AUTO.to_string(), ASCII.to_string(), UNICODE.to_string()];
+
+        let entries = match Self::dir().map(std::fs::read_dir) {
+            Some(Ok(entries)) => entries,
+            _ => return names,
+        };
+
+        let mut user_themes: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+            .filter(|name| !names.contains(name))
+            .collect();
+        user_themes.sort();
+
+        names.extend(user_themes);
+        names
+    }
+
+    pub fn load(name: &str) -> Result<Self> {
+        match name {
+            AUTO => Self::load(Self::detect()),
+            ASCII => Self::parse(ASCII_THEME),
+            UNICODE => Self::parse(UNICODE_THEME),
+            _ => {
+                let path = Self::dir()
+                    .map(|dir| dir.join(format!("{}.json", name)))
+                    .ok_or(error!("No config directory found"))?;
+
+                let data = std::fs::read_to_string(&path)
+                    .map_err(|e| error!("Theme {}: {}", path.display(), e))?;
+
+                Self::parse(&data)
+            }
+        }
+    }
+
+    // Unicode only when the locale says UTF-8 and the terminal is known to
+    // cope; the Linux console, serial terminals and the classic Windows
+    // console get the ASCII theme
+    pub fn detect() -> &'static str {
+        let term = std::env::var("TERM").unwrap_or_default();
+        if matches!(
+            term.as_str(),
+            "dumb" | "linux" | "vt100" | "vt102" | "vt220"
+        ) {
+            return ASCII;
+        }
+
+        if cfg!(windows) {
+            // Windows Terminal sets WT_SESSION, conhost doesn't
+            return if std::env::var_os("WT_SESSION").is_some() {
+                UNICODE
+            } else {
+                ASCII
+            };
+        }
+
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        if locale.contains("utf-8") || locale.contains("utf8") {
+            UNICODE
+        } else {
+            ASCII
+        }
+    }
+}
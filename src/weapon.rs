@@ -1,11 +1,14 @@
 use std::ops::{Deref, DerefMut};
 
 use crate::{
-    audio::{GameAudio, GameObjectSound},
+    animation::Animation,
     container::{Container, Direction, Point},
     gobj::GameObject,
 };
 
+// time a bullet takes to move one row
+pub const BULLET_TICK_DURATION: std::time::Duration = std::time::Duration::from_millis(30);
+
 #[derive(Debug, Clone)]
 pub struct Bullet {
     pub location: Container,
@@ -16,8 +19,6 @@ pub struct Bullet {
 
     pub gobj: GameObject,
     pub direction: Direction,
-
-    on_fire_audio: GameObjectSound,
 }
 
 impl Deref for Bullet {
@@ -35,19 +36,24 @@ impl DerefMut for Bullet {
 }
 
 impl Bullet {
-    pub fn new(x: usize, y: usize, direction: Direction) -> Self {
+    // `frames` are the theme's glyphs for this side's bullets
+    pub fn new(x: usize, y: usize, direction: Direction, frames: &[String]) -> Self {
         let container = Container::new(Point { x, y }, Point { x, y });
+        let frames: Vec<&str> = frames.iter().map(|f| f.as_str()).collect();
 
-        let on_fire_audio = GameObjectSound::new("assets/sounds/hit.mp3");
+        let mut gobj = GameObject::new(container, frames[0]);
+        gobj.set_animation(Animation::timed(
+            &frames,
+            std::time::Duration::from_millis(60),
+        ));
 
         Self {
             location: Container::new(Point { x, y }, Point { x, y }),
             speed: 1,
-            tick_duration: std::time::Duration::from_millis(30),
+            tick_duration: BULLET_TICK_DURATION,
             last_bullet_tick: None,
-            gobj: GameObject::new(container, "⌇"),
+            gobj,
             direction,
-            on_fire_audio,
         }
     }
 
@@ -82,12 +88,17 @@ impl Bullet {
             }
         } else {
             self.last_bullet_tick = Some(now);
-            self.on_fire_audio
-                .play()
-                .expect("Failed to play bullet audio");
         }
     }
 
+    pub fn shift_timers(&mut self, by: std::time::Duration) {
+        if let Some(t) = &mut self.last_bullet_tick {
+            *t += by;
+        }
+
+        self.gobj.shift_timers(by);
+    }
+
     pub fn next_pos(&self) -> Point {
         let mut pos = self.get_pos();
 
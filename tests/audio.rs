@@ -0,0 +1,125 @@
+use std::sync::{Mutex, MutexGuard};
+
+use invader::audio::{self, Channel};
+use invader::audio_backend::{AudioEvent, RecordingBackend};
+use invader::bot::Bot;
+use invader::container::Direction;
+use invader::game::Game;
+use invader::keyboard::Command;
+use invader::sounds::{SoundEvent, SoundManifest};
+
+// the backend, the tick and the cooldowns are global, so the tests take
+// turns instead of running in parallel
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn lock() -> MutexGuard<'static, ()> {
+    LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// a recording backend playing only the sounds in `manifest`
+fn record(manifest: &str) -> RecordingBackend {
+    let recorder = RecordingBackend::new();
+
+    audio::set_backend(Box::new(recorder.clone()));
+    audio::set_sound_manifest(SoundManifest::parse(manifest).unwrap());
+    audio::set_stereo(true);
+    audio::set_tick(0);
+
+    recorder
+}
+
+#[test]
+fn alien_destroyed_is_recorded_on_its_tick() {
+    let _lock = lock();
+    let recorder =
+        record(r#"{ "alien_destroyed": { "sound": "synth:explosion", "volume": 0.8 } }"#);
+
+    let mut game = Game::new(80, 30, 0.0, 99, 2, 4);
+    let mut bot = Bot::new(std::time::Duration::ZERO);
+    let mut hit_tick = None;
+
+    let started = std::time::Instant::now();
+    while started.elapsed() < std::time::Duration::from_secs(20) {
+        match bot.decide(&game) {
+            Some(Command::MoveLeft) => game.move_ship(Direction::LEFT),
+            Some(Command::MoveRight) => game.move_ship(Direction::RIGHT),
+            Some(Command::Fire) => game.fire_bullet(),
+            _ => (),
+        }
+
+        let score = game.get_score();
+        game.tick();
+        if game.get_score() > score {
+            hit_tick = Some(game.get_ticks());
+            break;
+        }
+
+        std::thread::sleep(std::time::Duration::from_millis(2));
+    }
+
+    let hit_tick = hit_tick.expect("the bot never hit an alien");
+    let log = recorder.get_log();
+
+    assert_eq!(log.len(), 1, "{:?}", log);
+    let (tick, event) = &log[0];
+    assert_eq!(*tick, hit_tick);
+    match event {
+        AudioEvent::Sound {
+            path,
+            channel,
+            volume,
+            pan,
+        } => {
+            assert_eq!(path, "synth:explosion");
+            assert_eq!(*channel, Channel::Sfx);
+            assert_eq!(*volume, 0.8);
+            assert!((-1.0..=1.0).contains(pan));
+        }
+        other => panic!("expected a sound, got {:?}", other),
+    }
+}
+
+#[test]
+fn play_event_at_waits_for_the_cooldown() {
+    let _lock = lock();
+    let recorder = record(
+        r#"{
+            "enemy_fire": { "sound": "synth:zap", "volume": 0.7, "cooldown_ms": 200 },
+            "player_fire": { "sound": "synth:laser", "volume": 1.0, "cooldown_ms": 0 }
+        }"#,
+    );
+
+    audio::play_event_at(SoundEvent::EnemyFire, -0.5).unwrap();
+    audio::play_event_at(SoundEvent::EnemyFire, 0.5).unwrap();
+    // other events don't share the cooldown
+    audio::play_event_at(SoundEvent::PlayerFire, 0.0).unwrap();
+    assert_eq!(recorder.get_sounds(), vec!["synth:zap", "synth:laser"]);
+
+    match &recorder.get_log()[0].1 {
+        AudioEvent::Sound { pan, .. } => assert_eq!(*pan, -0.5),
+        other => panic!("expected a sound, got {:?}", other),
+    }
+
+    std::thread::sleep(std::time::Duration::from_millis(250));
+    audio::play_event_at(SoundEvent::EnemyFire, 0.5).unwrap();
+    assert_eq!(
+        recorder.get_sounds(),
+        vec!["synth:zap", "synth:laser", "synth:zap"]
+    );
+}
+
+#[test]
+fn disabled_events_stay_silent_without_cooling_down() {
+    let _lock = lock();
+    let recorder = record(
+        r#"{ "enemy_fire": { "sound": "synth:zap", "volume": 0.7, "cooldown_ms": 60000 } }"#,
+    );
+
+    audio::set_events_enabled(false);
+    audio::play_event(SoundEvent::EnemyFire).unwrap();
+    audio::set_events_enabled(true);
+    assert!(recorder.get_sounds().is_empty());
+
+    audio::play_event(SoundEvent::EnemyFire).unwrap();
+    assert_eq!(recorder.get_sounds(), vec!["synth:zap"]);
+}
@@ -0,0 +1,43 @@
+use invader::config::Config;
+use invader::palette::Palette;
+
+// the only test in this binary, so pointing the config dir somewhere else
+// can't disturb another test
+#[test]
+fn settings_survive_a_save_and_load() {
+    let dir = std::env::temp_dir().join(format!("invader-config-{}", std::process::id()));
+    std::env::set_var("XDG_CONFIG_HOME", &dir);
+
+    // nothing saved yet
+    assert_eq!(
+        Config::load().unwrap().starting_lives,
+        Config::default().starting_lives
+    );
+
+    let mut config = Config::default();
+    config.volume = 0.4;
+    config.muted = true;
+    config.language = "ja".to_string();
+    config.palette = Palette::HighContrast;
+    config.key_bindings.fire = "f".to_string();
+    config.save().unwrap();
+
+    let loaded = Config::load().unwrap();
+    assert_eq!(loaded.volume, 0.4);
+    assert!(loaded.muted);
+    assert_eq!(loaded.language, "ja");
+    assert_eq!(loaded.palette, Palette::HighContrast);
+    assert_eq!(loaded.key_bindings.fire, "f");
+    assert_eq!(loaded.key_bindings.left, "Left");
+
+    // older files without the newer fields keep loading
+    std::fs::write(Config::path().unwrap(), r#"{ "volume": 0.2 }"#).unwrap();
+    let loaded = Config::load().unwrap();
+    assert_eq!(loaded.volume, 0.2);
+    assert_eq!(loaded.formation_rows, Config::default().formation_rows);
+
+    std::fs::write(Config::path().unwrap(), "{ not json").unwrap();
+    assert!(Config::load().is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
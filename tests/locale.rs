@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use invader::locale;
+
+// the language is global, the tests take turns changing it
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn lock() -> MutexGuard<'static, ()> {
+    LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn catalog(language: &str) -> HashMap<String, String> {
+    let path = format!(
+        "{}/assets/locales/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        language
+    );
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn every_catalog_has_every_english_key() {
+    let english = catalog("en");
+
+    for language in locale::languages().iter().skip(1) {
+        let messages = catalog(language);
+        let mut missing: Vec<&String> = english
+            .keys()
+            .filter(|k| !messages.contains_key(*k))
+            .collect();
+        missing.sort();
+
+        assert!(missing.is_empty(), "{} lacks {:?}", language, missing);
+    }
+}
+
+#[test]
+fn unknown_keys_fall_back_to_the_key() {
+    let _lock = lock();
+    locale::set_language("de").unwrap();
+
+    assert_eq!(locale::text("no.such.key"), "no.such.key");
+    assert_eq!(locale::text("hud.weapon"), catalog("de")["hud.weapon"]);
+}
+
+#[test]
+fn unknown_languages_keep_the_current_one() {
+    let _lock = lock();
+    locale::set_language("es").unwrap();
+
+    assert!(locale::set_language("xx").is_err());
+    assert_eq!(locale::get_language(), "es");
+    assert_eq!(locale::language_name("xx"), "xx");
+}
+
+#[test]
+fn placeholders_are_filled_in() {
+    let _lock = lock();
+    locale::set_language("en").unwrap();
+
+    let line = locale::text_with("hud.weapon", &[("weapon", "Laser".to_string())]);
+    assert_eq!(line, "Weapon: Laser");
+}
@@ -0,0 +1,59 @@
+use crossterm::style::Color;
+use invader::palette::{CellRole, Palette};
+
+#[test]
+fn role_codes_round_trip() {
+    let roles = CellRole::all();
+
+    for role in &roles {
+        assert_eq!(CellRole::from_code(role.get_code()), Some(*role));
+    }
+
+    let mut codes: Vec<char> = roles.iter().map(|r| r.get_code()).collect();
+    codes.sort();
+    codes.dedup();
+    assert_eq!(codes.len(), roles.len());
+
+    assert_eq!(CellRole::from_code('?'), None);
+}
+
+#[test]
+fn standard_keeps_the_terminal_colors() {
+    assert_eq!(Palette::Standard.background(), Color::Reset);
+
+    for role in CellRole::all() {
+        let expected = match role {
+            CellRole::Background => Color::DarkGrey,
+            _ => Color::Reset,
+        };
+        assert_eq!(Palette::Standard.foreground(role), expected);
+    }
+}
+
+#[test]
+fn high_contrast_sets_every_color() {
+    assert_eq!(Palette::HighContrast.background(), Color::Black);
+
+    for role in CellRole::all() {
+        assert_ne!(
+            Palette::HighContrast.foreground(role),
+            Color::Reset,
+            "{:?}",
+            role
+        );
+    }
+}
+
+#[test]
+fn colorblind_tells_friend_from_foe() {
+    let palette = Palette::Colorblind;
+
+    assert_ne!(
+        palette.foreground(CellRole::Ship),
+        palette.foreground(CellRole::Alien)
+    );
+    assert_ne!(
+        palette.foreground(CellRole::Bullet),
+        palette.foreground(CellRole::EnemyBullet)
+    );
+}
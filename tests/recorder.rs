@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+
+use invader::palette::{CellRole, Palette};
+use invader::recorder::Replay;
+
+// a fresh path in the temp dir, unique per test and process
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("invader-{}-{}", std::process::id(), name))
+}
+
+fn cells(rows: &[&str]) -> Vec<Vec<String>> {
+    rows.iter()
+        .map(|row| row.chars().map(|c| c.to_string()).collect())
+        .collect()
+}
+
+#[test]
+fn replay_round_trips_through_a_file() {
+    let mut replay = Replay::new();
+    replay.set_palette(Palette::Colorblind);
+
+    let first = cells(&["-M-", "|A|"]);
+    let first_roles = vec![
+        vec![CellRole::Border, CellRole::Alien, CellRole::Border],
+        vec![CellRole::Bullet, CellRole::Ship, CellRole::EnemyBullet],
+    ];
+    // a wide character is followed by the empty cell it covers
+    let second = vec![vec!["パ".to_string(), String::new(), "x".to_string()]];
+    let second_roles = vec![vec![CellRole::Text, CellRole::Text, CellRole::Particle]];
+
+    replay.push(0, &first, &first_roles);
+    replay.push(120, &second, &second_roles);
+
+    let path = temp_path("round-trip.replay");
+    replay.save(&path).unwrap();
+    let loaded = Replay::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.get_palette(), Palette::Colorblind);
+
+    let frames = loaded.get_frames();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].at_ms, 0);
+    assert_eq!(frames[0].cells, first);
+    assert_eq!(frames[0].roles, first_roles);
+    assert_eq!(frames[1].at_ms, 120);
+    assert_eq!(frames[1].cells, second);
+    assert_eq!(frames[1].roles, second_roles);
+}
+
+#[test]
+fn version_one_replays_load_as_text() {
+    let path = temp_path("v1.replay");
+    std::fs::write(
+        &path,
+        "invader-replay 1\nframe 0 2\nab\ncd\nframe 50 1\nef\n",
+    )
+    .unwrap();
+    let loaded = Replay::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.get_palette(), Palette::Standard);
+
+    let frames = loaded.get_frames();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].cells, cells(&["ab", "cd"]));
+    assert!(frames[0].roles.is_empty());
+    assert_eq!(frames[1].at_ms, 50);
+}
+
+#[test]
+fn malformed_replays_are_rejected() {
+    let cases = [
+        ("empty", ""),
+        ("header", "not a replay\n"),
+        ("palette", "invader-replay 2\nframe 0 1\na\nt\n"),
+        (
+            "frame",
+            "invader-replay 2\npalette \"Standard\"\nframe x 1\na\nt\n",
+        ),
+        (
+            "truncated",
+            "invader-replay 2\npalette \"Standard\"\nframe 0 2\na\nt\n",
+        ),
+        (
+            "role",
+            "invader-replay 2\npalette \"Standard\"\nframe 0 1\na\n?\n",
+        ),
+    ];
+
+    for (name, data) in cases {
+        let path = temp_path(&format!("{}.replay", name));
+        std::fs::write(&path, data).unwrap();
+        let result = Replay::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err(), "{} should not load", name);
+    }
+}
+
+#[test]
+fn frames_of_another_size_still_render() {
+    let mut replay = Replay::new();
+    replay.push(0, &cells(&["ab", "cd"]), &[]);
+    replay.push(10, &cells(&["abcdef"]), &[]);
+    // an edited file can go back in time
+    replay.push(5, &cells(&["a", "b", "c"]), &[]);
+
+    let path = temp_path("sizes.gif");
+    replay.render_gif(&path).unwrap();
+    let size = image::image_dimensions(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(size, (20, 40));
+}
+
+#[test]
+fn empty_replays_do_not_render() {
+    assert!(Replay::new().render_gif(&temp_path("empty.gif")).is_err());
+}
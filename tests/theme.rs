@@ -0,0 +1,53 @@
+use invader::theme::{Theme, ASCII, UNICODE};
+
+const MINIMAL: &str = r##"{
+    "name": "minimal",
+    "ship": ["A"],
+    "alien": ["M"],
+    "bullet": ["|"],
+    "enemy_bullet": ["!"],
+    "border": "-",
+    "banner": "#",
+    "life": "A",
+    "explosion": ["*"],
+    "debris": ["#"],
+    "spark": ["+"]
+}"##;
+
+#[test]
+fn bundled_themes_load() {
+    for name in [ASCII, UNICODE] {
+        let theme = Theme::load(name).unwrap();
+        assert_eq!(theme.name, name);
+    }
+}
+
+#[test]
+fn older_themes_get_the_default_background() {
+    let theme = Theme::parse(MINIMAL).unwrap();
+    let default = Theme::default();
+
+    assert_eq!(theme.stars, default.stars);
+    assert_eq!(theme.decorations, default.decorations);
+}
+
+#[test]
+fn themes_without_glyphs_are_rejected() {
+    for (field, empty) in [
+        ("\"ship\": [\"A\"]", "\"ship\": []"),
+        ("\"life\": \"A\"", "\"life\": \"\""),
+    ] {
+        let data = MINIMAL.replacen(field, empty, 1);
+        assert_ne!(data, MINIMAL);
+
+        let e = Theme::parse(&data).unwrap_err();
+        assert!(e.to_string().contains("minimal"), "{}", e);
+    }
+}
+
+#[test]
+fn themes_missing_a_field_are_rejected() {
+    let data = MINIMAL.replacen("\"banner\": \"#\",", "", 1);
+
+    assert!(Theme::parse(&data).is_err());
+}